@@ -0,0 +1,79 @@
+//! Write a small HOOMD trajectory of randomly placed particles, then reopen it and print a
+//! summary of every frame.
+//!
+//! Run with `cargo run --example hoomd_roundtrip`.
+
+use std::error::Error;
+
+use gsd::fl::{GSDFile, Metadata, Mode};
+use gsd::hoomd::HOOMDTrajectory;
+use ndarray::Array2;
+
+const N: usize = 8;
+const FRAMES: u64 = 4;
+const L: f32 = 10.0;
+
+/// Minimal xorshift generator, so the example needs no extra dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn next_f32(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let path = std::env::temp_dir().join("hoomd_roundtrip.gsd");
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+    {
+        let metadata = Metadata {
+            application: "hoomd_roundtrip example".to_owned(),
+            schema: "hoomd".to_owned(),
+            schema_version: (1, 4),
+        };
+        let mut file = GSDFile::open(&path, Mode::Write, Some(metadata))?;
+
+        for frame in 0..FRAMES {
+            file.write_chunk("configuration/step", &vec![frame * 1000])?;
+            if frame == 0 {
+                // static data is only stored in frame 0, later frames inherit it
+                file.write_chunk("configuration/box", &vec![L, L, L, 0.0, 0.0, 0.0])?;
+                file.write_chunk("particles/N", &vec![N as u32])?;
+                file.write_string_chunk("particles/types", &["A", "B"])?;
+                let typeid: Vec<u32> = (0..N as u32).map(|i| i % 2).collect();
+                file.write_chunk("particles/typeid", &typeid)?;
+            }
+            let position = Array2::from_shape_fn((N, 3), |_| L * (rng.next_f32() - 0.5));
+            file.write_chunk("particles/position", &position)?;
+            file.end_frame()?;
+        }
+    }
+
+    let file = GSDFile::open(&path, Mode::Read, None)?;
+    println!("{} frames in {}", file.nframes(), path.display());
+    let trajectory = HOOMDTrajectory::new(file);
+
+    for (frame, snapshot) in trajectory.try_iter().enumerate() {
+        let snapshot = snapshot?;
+        let particles = &snapshot.particles;
+        let centroid = particles
+            .position
+            .as_ref()
+            .and_then(|position| position.mean_axis(ndarray::Axis(0)));
+        let n_type_b = particles
+            .typeid
+            .as_ref()
+            .map_or(0, |typeid| typeid.iter().filter(|&&t| t == 1).count());
+        println!(
+            "frame {}: N = {}, type B particles = {}, centroid = {:?}",
+            frame, particles.n, n_type_b, centroid
+        );
+    }
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
@@ -1,53 +1,394 @@
 use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
     ffi::{c_void, CStr, CString},
+    fmt,
+    io::Write,
+    marker::PhantomData,
     mem::MaybeUninit,
+    path::Path,
     ptr,
+    str::FromStr,
 };
 
 use crate::*;
 use gsd_sys as libgsd;
 
-use log::debug;
+use log::{debug, error};
 use ndarray::prelude::*;
+use ndarray::CowArray;
 
 /// Raise the appropriate error type.
 /// ## Arguments
 /// * `retval`: Return value from a gsd C API call
 /// * `extra`: Extra string to pass along with the exception
 ///
-fn check_gsd_errors(retval: i32, extra: &str) -> Result<(), String> {
+fn check_gsd_errors(retval: i32, extra: &str) -> Result<(), GsdError> {
+    let extra = extra.to_owned();
     match retval {
         libgsd::gsd_error_GSD_SUCCESS => Ok(()),
-        libgsd::gsd_error_GSD_ERROR_IO => Err(format!("I/O error: {}", 2)),
-        libgsd::gsd_error_GSD_ERROR_INVALID_ARGUMENT => {
-            Err(format!("Invalid gsd argument: {}", extra))
-        }
-        libgsd::gsd_error_GSD_ERROR_NOT_A_GSD_FILE => Err(format!("Not a GSD file: {}", extra)),
+        libgsd::gsd_error_GSD_ERROR_IO => Err(GsdError::IO(extra)),
+        libgsd::gsd_error_GSD_ERROR_INVALID_ARGUMENT => Err(GsdError::InvalidArgument(extra)),
+        libgsd::gsd_error_GSD_ERROR_NOT_A_GSD_FILE => Err(GsdError::NotAFile(extra)),
         libgsd::gsd_error_GSD_ERROR_INVALID_GSD_FILE_VERSION => {
-            Err(format!("Unsupported GSD file version: {}", extra))
+            Err(GsdError::InvalidFileVersion(extra))
         }
         libgsd::gsd_error_GSD_ERROR_MEMORY_ALLOCATION_FAILED => {
-            Err(format!("Memory allocation failed: {}", extra))
+            Err(GsdError::MemoryAllocFailed(extra))
         }
-        libgsd::gsd_error_GSD_ERROR_FILE_CORRUPT => Err(format!("Corrupt GSD file: {}", extra)),
-        libgsd::gsd_error_GSD_ERROR_NAMELIST_FULL => {
-            Err(format!("GSD namelist is full: {}", extra))
+        libgsd::gsd_error_GSD_ERROR_FILE_CORRUPT => Err(GsdError::FileCorrupt(extra)),
+        libgsd::gsd_error_GSD_ERROR_NAMELIST_FULL => Err(GsdError::NamelistFull(extra)),
+        libgsd::gsd_error_GSD_ERROR_FILE_MUST_BE_WRITABLE => Err(GsdError::NotWritable(extra)),
+        libgsd::gsd_error_GSD_ERROR_FILE_MUST_BE_READABLE => Err(GsdError::NotReadable(extra)),
+        _ => Err(GsdError::Unknown(retval, extra)),
+    }
+}
+
+/// Decode the type byte of an index entry, which comes straight from the file and so
+/// cannot be trusted to be a valid `GSDType`.
+pub(crate) fn entry_type(
+    index_entry: &libgsd::gsd_index_entry,
+    file_name: &str,
+) -> Result<GSDType, GsdError> {
+    GSDType::from_raw(index_entry.type_).ok_or_else(|| {
+        GsdError::FileCorrupt(format!(
+            "invalid chunk type {} in: {}",
+            index_entry.type_, file_name
+        ))
+    })
+}
+
+/// Write `data` to `out` as CSV, one line per row, formatting each element with `format`.
+fn write_csv<T, W: Write>(
+    data: &Array2<T>,
+    out: &mut W,
+    format: impl Fn(&T) -> String,
+) -> std::io::Result<()> {
+    for row in data.outer_iter() {
+        let fields: Vec<String> = row.iter().map(&format).collect();
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+/// Reverse the bytes of each `size`-byte element of `bytes`, converting between little-endian
+/// file data and big-endian host data.
+pub(crate) fn swap_element_bytes(bytes: &mut [u8], size: usize) {
+    if size > 1 {
+        for element in bytes.chunks_exact_mut(size) {
+            element.reverse();
         }
-        libgsd::gsd_error_GSD_ERROR_FILE_MUST_BE_WRITABLE => {
-            Err(format!("File must be writable: {}", extra))
+    }
+}
+
+/// 64-bit FNV-1a hash, which unlike `std`'s hashers is specified and so stable across runs
+/// and Rust versions.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
         }
-        libgsd::gsd_error_GSD_ERROR_FILE_MUST_BE_READABLE => {
-            Err(format!("File must be readable: {}", extra))
+    }
+}
+
+/// Type, shape and flags of a chunk, as recorded in the file index. The index records the
+/// frame of a chunk but not the simulation step, which HOOMD stores in `configuration/step`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkInfo {
+    pub type_: GSDType,
+    pub n: u64,
+    pub m: u32,
+    /// Raw flags byte of the index entry, which the GSD format reserves for per-chunk options
+    /// such as compression. No released version of GSD sets it, so chunks written by GSD
+    /// report 0.
+    pub flags: u8,
+}
+
+impl ChunkInfo {
+    /// Size of the chunk payload in bytes.
+    pub fn nbytes(&self) -> usize {
+        self.n as usize * self.m as usize * self.type_.size_bytes()
+    }
+}
+
+/// How one chunk differs between two frames, see `GSDFile::diff_frames`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkDiff {
+    pub name: String,
+    pub present_in_a: bool,
+    pub present_in_b: bool,
+    /// Whether both frames store the chunk with the same type, shape and bytes.
+    pub bytes_equal: bool,
+}
+
+/// Data accepted by `GSDFile::write_chunk`: owned arrays, views and copy-on-write arrays by
+/// value, and references to arrays, vectors and slices. Only owned arrays that are not in
+/// standard layout are copied before writing.
+pub trait ChunkSource<'a, T, D: Dimension> {
+    fn into_chunk_array(self) -> CowArray<'a, T, D>;
+}
+
+impl<'a, T, D: Dimension> ChunkSource<'a, T, D> for Array<T, D> {
+    fn into_chunk_array(self) -> CowArray<'a, T, D> {
+        self.into()
+    }
+}
+
+impl<'a, T, D: Dimension> ChunkSource<'a, T, D> for ArrayView<'a, T, D> {
+    fn into_chunk_array(self) -> CowArray<'a, T, D> {
+        self.into()
+    }
+}
+
+impl<'a, T, D: Dimension> ChunkSource<'a, T, D> for CowArray<'a, T, D> {
+    fn into_chunk_array(self) -> CowArray<'a, T, D> {
+        self
+    }
+}
+
+impl<'a, T, S, D> ChunkSource<'a, T, D> for &'a ArrayBase<S, D>
+where
+    S: ndarray::Data<Elem = T>,
+    D: Dimension,
+{
+    fn into_chunk_array(self) -> CowArray<'a, T, D> {
+        self.view().into()
+    }
+}
+
+impl<'a, T> ChunkSource<'a, T, Ix1> for &'a [T] {
+    fn into_chunk_array(self) -> CowArray<'a, T, Ix1> {
+        ArrayView1::from(self).into()
+    }
+}
+
+impl<'a, T, const N: usize> ChunkSource<'a, T, Ix1> for &'a [T; N] {
+    fn into_chunk_array(self) -> CowArray<'a, T, Ix1> {
+        ArrayView1::from(&self[..]).into()
+    }
+}
+
+impl<'a, T> ChunkSource<'a, T, Ix1> for &'a Vec<T> {
+    fn into_chunk_array(self) -> CowArray<'a, T, Ix1> {
+        ArrayView1::from(self.as_slice()).into()
+    }
+}
+
+/// Borrowed data of one chunk, with a variant for each type GSD can store. Used to write a
+/// whole frame with `GSDFile::write_frame`. One dimensional arrays are stored as `(N, 1)`.
+#[derive(Debug, Clone)]
+pub enum ChunkData<'a> {
+    UINT8(ArrayView2<'a, u8>),
+    UINT16(ArrayView2<'a, u16>),
+    UINT32(ArrayView2<'a, u32>),
+    UINT64(ArrayView2<'a, u64>),
+    INT8(ArrayView2<'a, i8>),
+    INT16(ArrayView2<'a, i16>),
+    INT32(ArrayView2<'a, i32>),
+    INT64(ArrayView2<'a, i64>),
+    FLOAT(ArrayView2<'a, f32>),
+    DOUBLE(ArrayView2<'a, f64>),
+}
+
+macro_rules! chunk_data_from {
+    ($($type:ty => $variant:ident),*) => {
+        $(
+            impl<'a> From<ArrayView2<'a, $type>> for ChunkData<'a> {
+                fn from(data: ArrayView2<'a, $type>) -> Self {
+                    ChunkData::$variant(data)
+                }
+            }
+
+            impl<'a> From<ArrayView1<'a, $type>> for ChunkData<'a> {
+                fn from(data: ArrayView1<'a, $type>) -> Self {
+                    ChunkData::$variant(data.insert_axis(Axis(1)))
+                }
+            }
+        )*
+    };
+}
+
+chunk_data_from!(
+    u8 => UINT8,
+    u16 => UINT16,
+    u32 => UINT32,
+    u64 => UINT64,
+    i8 => INT8,
+    i16 => INT16,
+    i32 => INT32,
+    i64 => INT64,
+    f32 => FLOAT,
+    f64 => DOUBLE
+);
+
+impl<'a> ChunkData<'a> {
+    /// `(N, M)` shape of the data.
+    fn dim(&self) -> (usize, usize) {
+        match self {
+            ChunkData::UINT8(data) => data.dim(),
+            ChunkData::UINT16(data) => data.dim(),
+            ChunkData::UINT32(data) => data.dim(),
+            ChunkData::UINT64(data) => data.dim(),
+            ChunkData::INT8(data) => data.dim(),
+            ChunkData::INT16(data) => data.dim(),
+            ChunkData::INT32(data) => data.dim(),
+            ChunkData::INT64(data) => data.dim(),
+            ChunkData::FLOAT(data) => data.dim(),
+            ChunkData::DOUBLE(data) => data.dim(),
+        }
+    }
+
+    fn write_to(&self, file: &mut GSDFile, name: &str) -> Result<(), GsdError> {
+        match self {
+            ChunkData::UINT8(data) => file.write_chunk(name, data.view()),
+            ChunkData::UINT16(data) => file.write_chunk(name, data.view()),
+            ChunkData::UINT32(data) => file.write_chunk(name, data.view()),
+            ChunkData::UINT64(data) => file.write_chunk(name, data.view()),
+            ChunkData::INT8(data) => file.write_chunk(name, data.view()),
+            ChunkData::INT16(data) => file.write_chunk(name, data.view()),
+            ChunkData::INT32(data) => file.write_chunk(name, data.view()),
+            ChunkData::INT64(data) => file.write_chunk(name, data.view()),
+            ChunkData::FLOAT(data) => file.write_chunk(name, data.view()),
+            ChunkData::DOUBLE(data) => file.write_chunk(name, data.view()),
         }
-        _ => Err(format!("Unknown error: {}", extra)),
     }
 }
 
+/// Mode to open a GSD file with. Each variant corresponds to one of the mode strings accepted
+/// by `try_new` and the `open!` macro.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// `"wb"`: create or overwrite the file for writing only.
+    Write,
+    /// `"rb+"`: open an existing file for reading and writing. New frames are appended after
+    /// the existing ones, which stay readable through the same handle.
+    ReadWrite,
+    /// `"rb"`: open an existing file for reading only.
+    #[default]
+    Read,
+    /// `"wb+"`: create or overwrite the file for reading and writing.
+    ReadWriteTruncate,
+    /// `"xb"`: create a new file for writing only, failing with `GsdError::AlreadyExists` if it
+    /// already exists.
+    ExclusiveWrite,
+    /// `"xb+"`: create a new file for reading and writing, failing with
+    /// `GsdError::AlreadyExists` if it already exists.
+    ExclusiveReadWrite,
+    /// `"ab"`: open an existing file to append frames to it.
+    Append,
+}
+
+impl Mode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Mode::Write => "wb",
+            Mode::ReadWrite => "rb+",
+            Mode::Read => "rb",
+            Mode::ReadWriteTruncate => "wb+",
+            Mode::ExclusiveWrite => "xb",
+            Mode::ExclusiveReadWrite => "xb+",
+            Mode::Append => "ab",
+        }
+    }
+
+    fn open_flag(&self) -> OpenFlag {
+        match self {
+            Mode::Read => OpenFlag::Readonly,
+            Mode::ReadWrite | Mode::ReadWriteTruncate | Mode::ExclusiveReadWrite => {
+                OpenFlag::Readwrite
+            }
+            Mode::Write | Mode::ExclusiveWrite | Mode::Append => OpenFlag::Append,
+        }
+    }
+
+    /// Whether opening creates a new file, which requires `Metadata` for its header.
+    fn overwrite(&self) -> bool {
+        matches!(
+            self,
+            Mode::Write | Mode::ReadWriteTruncate | Mode::ExclusiveWrite | Mode::ExclusiveReadWrite
+        )
+    }
+
+    fn exclusive_create(&self) -> bool {
+        matches!(self, Mode::ExclusiveWrite | Mode::ExclusiveReadWrite)
+    }
+}
+
+impl FromStr for Mode {
+    type Err = GsdError;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "wb" => Ok(Mode::Write),
+            "wb+" => Ok(Mode::ReadWriteTruncate),
+            "rb" => Ok(Mode::Read),
+            "rb+" => Ok(Mode::ReadWrite),
+            "xb" => Ok(Mode::ExclusiveWrite),
+            "xb+" => Ok(Mode::ExclusiveReadWrite),
+            "ab" => Ok(Mode::Append),
+            _ => Err(GsdError::InvalidArgument(
+                "mode must be 'wb', 'wb+', 'rb', 'rb+', 'xb', 'xb+', or 'ab'".to_owned(),
+            )),
+        }
+    }
+}
+
+/// Largest write buffer, in bytes, that `GSDFile::reserve` will request.
+pub const MAX_RESERVED_WRITE_BUFFER_SIZE: u64 = 1 << 30;
+
+/// Most chunk names, and most frames per name, whose index entries `GSDFile` memoizes before
+/// the memo is reset, so that scanning long trajectories does not grow it without bound.
+const INDEX_CACHE_CAPACITY: usize = 4096;
+
+/// Version of the file layer written by the GSD library this crate links, as `(major, minor)`.
+/// It mirrors `GSD_CURRENT_FILE_VERSION` in `gsd.c`, which the C library does not export, and
+/// has to be bumped along with gsd-sys.
+pub const CURRENT_GSD_VERSION: (u32, u32) = (2, 0);
+
+/// Header fields of a newly created file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    pub application: String,
+    pub schema: String,
+    pub schema_version: (u32, u32),
+}
+
+/// Header of an open file, as returned by `GSDFile::header`. Versions are given both as
+/// `(major, minor)` and in their raw encoding, `major << 16 | minor`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub application: String,
+    pub schema: String,
+    pub schema_version: (u32, u32),
+    pub gsd_version: (u32, u32),
+    pub raw_schema_version: u32,
+    pub raw_gsd_version: u32,
+}
+
+/// An open GSD file.
+///
+/// A `GSDFile` is `Send` but not `Sync`: the handle exclusively owns its file descriptor and
+/// buffers, so it can be moved to another thread, but lookups cache index entries in a
+/// `RefCell`, so it cannot be shared between threads. To read from several threads at once,
+/// give each its own handle from `try_clone_read`.
 #[derive(Default)]
 pub struct GSDFile {
     name: String,
-    mode: String,
+    mode: Mode,
     handle: libgsd::gsd_handle,
+    nframes: usize,
+    frame_chunks: HashSet<String>,
+    /// Index entries already resolved by `find_chunk` by name and then frame, null where the
+    /// chunk was not found. Keyed by name first so hits are looked up with a `&str`. The
+    /// pointers refer into the handle's file index and are cleared whenever it changes.
+    index_cache: RefCell<HashMap<String, HashMap<usize, *const libgsd::gsd_index_entry>>>,
 }
 
 impl GSDFile {
@@ -57,124 +398,207 @@ impl GSDFile {
         application: Option<String>,
         schema: Option<String>,
         schema_version: Option<(u32, u32)>,
-    ) -> Result<Self, String> {
-        let mut exclusive_create = 0i32;
-        let mut overwrite = false;
-
-        let c_flags = match mode.as_str() {
-            "wb" => {
-                overwrite = true;
-                OpenFlag::Append
-            }
-            "wb+" => {
-                overwrite = true;
-                OpenFlag::Readwrite
-            }
-            "rb" => OpenFlag::Readonly,
-            "rb+" => OpenFlag::Readwrite,
-            "xb" => {
-                overwrite = true;
-                exclusive_create = 1;
-                OpenFlag::Append
-            }
-            "xb+" => {
-                overwrite = true;
-                exclusive_create = 1;
-                OpenFlag::Readwrite
-            }
-            "ab" => OpenFlag::Append,
-            _ => {
-                return Err(
-                    "mode must be 'wb', 'wb+', 'rb', 'rb+', 'xb', 'xb+', or 'ab'".to_owned(),
-                )
-            }
+    ) -> Result<Self, GsdError> {
+        let mode: Mode = mode.parse()?;
+        let metadata = match (application, schema, schema_version) {
+            (Some(application), Some(schema), Some(schema_version)) => Some(Metadata {
+                application,
+                schema,
+                schema_version,
+            }),
+            _ => None,
+        };
+
+        Self::open(name, mode, metadata)
+    }
+
+    /// Open the existing file at `path` for reading only.
+    pub fn open_read<P: AsRef<Path>>(path: P) -> Result<Self, GsdError> {
+        Self::open(path, Mode::Read, None)
+    }
+
+    /// Create or overwrite the file at `path` for reading and writing.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        application: &str,
+        schema: &str,
+        schema_version: (u32, u32),
+    ) -> Result<Self, GsdError> {
+        let metadata = Metadata {
+            application: application.to_owned(),
+            schema: schema.to_owned(),
+            schema_version,
         };
+        Self::open(path, Mode::ReadWriteTruncate, Some(metadata))
+    }
+
+    /// Open a new, independent read-only handle to the same file, e.g. to read on another
+    /// thread. It sees the frames committed when it is opened; frames still held in the write
+    /// buffer of this handle are not visible to it.
+    pub fn try_clone_read(&self) -> Result<GSDFile, GsdError> {
+        Self::open(&self.name, Mode::Read, None)
+    }
 
-        let uninit_handle = MaybeUninit::<libgsd::gsd_handle>::uninit();
-        let mut raw_handle = unsafe { *uninit_handle.as_ptr() };
+    /// Start building the options to open a file with.
+    pub fn builder() -> GSDFileBuilder {
+        GSDFileBuilder::new()
+    }
+
+    /// Open the file at `path`. `metadata` is required by the modes that create a new file and
+    /// ignored otherwise.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        mode: Mode,
+        metadata: Option<Metadata>,
+    ) -> Result<Self, GsdError> {
+        let path = path.as_ref();
+        let name = path
+            .to_str()
+            .ok_or_else(|| {
+                GsdError::InvalidArgument(format!("path is not valid UTF-8: {}", path.display()))
+            })?
+            .to_owned();
+        let c_name = CString::new(name.clone()).expect("CString::new failed");
+        let c_flags = mode.open_flag();
 
-        let retval = if overwrite {
-            if application.is_none() || schema.is_none() || schema_version.is_none() {
-                return Err(
+        let mut uninit_handle = MaybeUninit::<libgsd::gsd_handle>::uninit();
+
+        let retval = if mode.overwrite() {
+            let metadata = metadata.ok_or_else(|| {
+                GsdError::InvalidArgument(
                     "If overwriting, must specify application, schema, and schema_version"
                         .to_owned(),
-                );
-            }
+                )
+            })?;
 
-            let c_name = CString::new(name.to_owned()).expect("CString::new failed");
-            let c_application = CString::new(application.unwrap()).expect("CString::new failed");
-            let c_schema = CString::new(schema.unwrap()).expect("CString::new failed");
+            let c_application = CString::new(metadata.application).expect("CString::new failed");
+            let c_schema = CString::new(metadata.schema).expect("CString::new failed");
 
-            let schema_version = schema_version.unwrap();
+            let schema_version = metadata.schema_version;
 
             let c_schema_version =
                 unsafe { libgsd::gsd_make_version(schema_version.0, schema_version.1) };
 
             unsafe {
                 libgsd::gsd_create_and_open(
-                    &mut raw_handle as *mut libgsd::gsd_handle,
+                    uninit_handle.as_mut_ptr(),
                     c_name.as_ptr(),
                     c_application.as_ptr(),
                     c_schema.as_ptr(),
                     c_schema_version,
                     c_flags as u32,
-                    exclusive_create,
+                    mode.exclusive_create() as i32,
                 )
             }
         } else {
-            let c_name = CString::new(name.clone()).expect("CString::new failed");
-
-            unsafe {
-                libgsd::gsd_open(
-                    &mut raw_handle as *mut libgsd::gsd_handle,
-                    c_name.as_ptr(),
-                    c_flags as u32,
-                )
-            }
+            unsafe { libgsd::gsd_open(uninit_handle.as_mut_ptr(), c_name.as_ptr(), c_flags as u32) }
         };
 
-        check_gsd_errors(retval, &name).unwrap();
+        if retval == libgsd::gsd_error_GSD_ERROR_IO && mode.exclusive_create() && path.exists() {
+            // the C API reports the failed exclusive create as a generic I/O error
+            return Err(GsdError::AlreadyExists(name));
+        }
+        check_gsd_errors(retval, &name)?;
+
+        // the handle is only initialized once the C API reports success
+        let handle = unsafe { uninit_handle.assume_init() };
 
-        let name = name.to_owned();
-        let mode = mode.to_owned();
-        Ok(GSDFile {
+        let mut file = GSDFile {
             name,
             mode,
-            handle: raw_handle,
-        })
+            handle,
+            nframes: 0,
+            frame_chunks: HashSet::new(),
+            index_cache: RefCell::new(HashMap::new()),
+        };
+        file.refresh_nframes();
+        Ok(file)
     }
 
+    /// Number of frames in the file. The count is cached, and refreshed whenever this handle
+    /// adds or removes frames.
     pub fn nframes(&self) -> usize {
+        self.nframes
+    }
+
+    fn refresh_nframes(&mut self) {
         let retval =
-            unsafe { libgsd::gsd_get_nframes(&self.handle as *const libgsd::gsd_handle) };
-        retval as usize
+            unsafe { libgsd::gsd_get_nframes(&mut self.handle as *mut libgsd::gsd_handle) };
+        self.nframes = retval as usize;
     }
 
-    pub fn truncate(&mut self) {
+    /// Largest number of bytes buffered in memory before chunk data is written to the file.
+    pub fn max_write_buffer_size(&mut self) -> u64 {
+        unsafe {
+            libgsd::gsd_get_maximum_write_buffer_size(&mut self.handle as *mut libgsd::gsd_handle)
+        }
+    }
+
+    /// Set the size of the write buffer. Larger buffers need fewer writes for large frames.
+    pub fn set_max_write_buffer_size(&mut self, bytes: u64) -> Result<(), GsdError> {
+        let retval = unsafe {
+            libgsd::gsd_set_maximum_write_buffer_size(
+                &mut self.handle as *mut libgsd::gsd_handle,
+                bytes,
+            )
+        };
+        check_gsd_errors(retval, &self.name)
+    }
+
+    /// Advise that about `frames` frames of `approx_bytes_per_frame` bytes each will be
+    /// written, growing the write buffer so they are written in fewer, larger writes. This is
+    /// only a hint: the buffer never shrinks, is capped at `MAX_RESERVED_WRITE_BUFFER_SIZE`, and
+    /// the written data does not depend on it.
+    pub fn reserve(&mut self, approx_bytes_per_frame: u64, frames: usize) {
+        let bytes = approx_bytes_per_frame
+            .saturating_mul(frames as u64)
+            .min(MAX_RESERVED_WRITE_BUFFER_SIZE);
+        if bytes > self.max_write_buffer_size() {
+            if let Err(err) = self.set_max_write_buffer_size(bytes) {
+                debug!(
+                    "Ignoring write buffer size hint of {} bytes: {}",
+                    bytes, err
+                );
+            }
+        }
+    }
+
+    /// Remove all frames from the file and return how many were removed. `gsd_truncate` keeps
+    /// no frames, not even frame 0, so this is the frame count before truncating. Fails with
+    /// `GsdError::NotWritable` when the file was opened read-only.
+    pub fn truncate(&mut self) -> Result<usize, GsdError> {
+        self.check_writable("truncate")?;
+        let nframes = self.nframes;
         let retval = unsafe { libgsd::gsd_truncate(&mut self.handle as *mut libgsd::gsd_handle) };
+        self.refresh_nframes();
+        self.clear_cache();
 
-        check_gsd_errors(retval, &self.name).unwrap();
+        check_gsd_errors(retval, &self.name)?;
+        Ok(nframes - self.nframes)
     }
 
+    /// Write `data` as chunk `name` of the current frame. One dimensional data is stored as
+    /// `(N, 1)`; see `ChunkSource` for the accepted inputs.
     pub fn write_chunk<'a, T, D, const I: usize>(
         &mut self,
         name: &str,
         data: D,
-    ) -> Result<(), String>
+    ) -> Result<(), GsdError>
     where
-        D: Into<ArrayView<'a, T, Dim<[usize; I]>>>,
+        D: ChunkSource<'a, T, Dim<[usize; I]>>,
         T: 'a + Clone + num_traits::Num,
         Dim<[usize; I]>: Dimension,
     {
-        let data: ArrayView<T, Dim<[usize; I]>> = data.into();
+        self.check_writable("write chunks to")?;
+        let data = data.into_chunk_array();
         let dim = data.raw_dim();
         let n;
         let m;
         if dim.ndim() > 2 {
-            return Err(format!(
+            return Err(GsdError::InvalidArgument(format!(
                 "GSD can only write 1 or 2 dimensional arrays: {}",
                 name
-            ));
+            )));
         } else if dim.ndim() == 2 {
             n = dim[0];
             m = dim[1];
@@ -182,88 +606,717 @@ impl GSDFile {
             n = dim[0];
             m = 1;
         }
+        // chunks may have no rows, but the C API rejects chunks without columns
+        if m == 0 {
+            return Err(GsdError::InvalidArgument(format!(
+                "chunk {} must have at least one column",
+                name
+            )));
+        }
 
         let data = data.as_standard_layout();
-
-        let gsd_type = GSDType::from_type::<T>();
-        let c_name = CString::new(name).expect("CString::new failed");
-
-        let retval = unsafe {
-            libgsd::gsd_write_chunk(
-                &mut self.handle as *mut libgsd::gsd_handle,
-                c_name.as_ptr(),
-                gsd_type as u32,
+        let gsd_type = GSDType::try_from_type::<T>()?;
+        if cfg!(target_endian = "big") {
+            // GSD files are little-endian, write a byte swapped copy
+            let size = gsd_type.size_bytes();
+            let mut bytes = unsafe {
+                std::slice::from_raw_parts(data.as_ptr() as *const u8, data.len() * size)
+            }
+            .to_vec();
+            swap_element_bytes(&mut bytes, size);
+            return unsafe {
+                self.write_raw_chunk(
+                    name,
+                    gsd_type,
+                    n as u64,
+                    m as u32,
+                    bytes.as_ptr() as *const c_void,
+                )
+            };
+        }
+        unsafe {
+            self.write_raw_chunk(
+                name,
+                gsd_type,
                 n as u64,
                 m as u32,
-                0,
                 data.as_ptr() as *const c_void,
             )
+        }
+    }
+
+    /// Write `n * m` elements of type `gsd_type` at `data` as chunk `name`.
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to `n * m * gsd_type.size_bytes()` readable bytes.
+    unsafe fn write_raw_chunk(
+        &mut self,
+        name: &str,
+        gsd_type: GSDType,
+        n: u64,
+        m: u32,
+        data: *const c_void,
+    ) -> Result<(), GsdError> {
+        if self.frame_chunks.contains(name) {
+            return Err(GsdError::InvalidArgument(format!(
+                "chunk {} was already written to the current frame of: {}",
+                name, self.name
+            )));
+        }
+
+        let c_name = CString::new(name).expect("CString::new failed");
+
+        let retval = libgsd::gsd_write_chunk(
+            &mut self.handle as *mut libgsd::gsd_handle,
+            c_name.as_ptr(),
+            gsd_type as u32,
+            n,
+            m,
+            0,
+            data,
+        );
+
+        check_gsd_errors(retval, &self.name)?;
+        self.frame_chunks.insert(name.to_owned());
+        Ok(())
+    }
+
+    /// Write `data` as chunk `name` unless `reference_frame` already stores it with the same
+    /// type, shape and bytes, and return whether it was written. Writing only what changed
+    /// from frame 0 is the HOOMD convention for static data. The file must be readable.
+    pub fn write_chunk_if_changed<'a, T, D, const I: usize>(
+        &mut self,
+        name: &str,
+        data: D,
+        reference_frame: usize,
+    ) -> Result<bool, GsdError>
+    where
+        D: ChunkSource<'a, T, Dim<[usize; I]>>,
+        T: 'a + Clone + num_traits::Num,
+        Dim<[usize; I]>: Dimension,
+    {
+        self.check_readable()?;
+        self.check_frame(reference_frame)?;
+        let data = data.into_chunk_array();
+        let data = data.as_standard_layout();
+        let shape = match *data.shape() {
+            [n] => Some((n as u64, 1)),
+            [n, m] => Some((n as u64, m as u32)),
+            _ => None,
         };
+        if self.chunk_exists(reference_frame, name) {
+            let (stored, type_, stored_shape) = self.read_chunk_bytes(reference_frame, name)?;
+            if type_ == GSDType::try_from_type::<T>()? && Some(stored_shape) == shape {
+                let size = type_.size_bytes();
+                let bytes = unsafe {
+                    std::slice::from_raw_parts(data.as_ptr() as *const u8, data.len() * size)
+                };
+                let unchanged = if cfg!(target_endian = "big") {
+                    let mut bytes = bytes.to_vec();
+                    swap_element_bytes(&mut bytes, size);
+                    bytes == stored
+                } else {
+                    bytes == stored
+                };
+                if unchanged {
+                    return Ok(false);
+                }
+            }
+        }
+        self.write_chunk(name, data.view())?;
+        Ok(true)
+    }
 
-        check_gsd_errors(retval, &self.name)
+    /// Write an array of any dimensionality, flattening all but the leading dimension into `M`.
+    /// The full shape is stored in the companion `u64` chunk `{name}/shape`, which
+    /// `read_chunk_nd` uses to restore the array.
+    pub fn write_chunk_nd<'a, T, D, const I: usize>(
+        &mut self,
+        name: &str,
+        data: D,
+    ) -> Result<(), GsdError>
+    where
+        D: ChunkSource<'a, T, Dim<[usize; I]>>,
+        T: 'a + Clone + num_traits::Num,
+        Dim<[usize; I]>: Dimension,
+    {
+        let data = data.into_chunk_array();
+        if I == 0 {
+            return Err(GsdError::InvalidArgument(format!(
+                "GSD cannot write 0 dimensional arrays: {}",
+                name
+            )));
+        }
+
+        let shape: Vec<u64> = data.shape().iter().map(|&d| d as u64).collect();
+        let n = data.shape()[0];
+        let m: usize = data.shape()[1..].iter().product();
+
+        let data = data.as_standard_layout();
+        let data = data
+            .view()
+            .into_shape((n, m))
+            .map_err(|e| GsdError::InvalidArgument(format!("{}: {}", name, e)))?;
+
+        self.write_chunk(name, data)?;
+        self.write_chunk(&format!("{}/shape", name), &shape)
+    }
+
+    /// Write a list of strings as a 2-D `INT8` chunk with one NUL-padded row per string, the
+    /// layout HOOMD uses for `particles/types`. Rows are one byte wider than the longest
+    /// string so that every entry stays NUL terminated.
+    pub fn write_string_chunk(&mut self, name: &str, strings: &[&str]) -> Result<(), GsdError> {
+        let width = strings.iter().map(|s| s.len()).max().unwrap_or(0) + 1;
+        let mut data = Array2::<i8>::zeros((strings.len(), width));
+        for (mut row, string) in data.outer_iter_mut().zip(strings) {
+            for (c, &b) in row.iter_mut().zip(string.as_bytes()) {
+                *c = b as i8;
+            }
+        }
+        self.write_chunk(name, &data)
     }
 
-    pub fn end_frame(&mut self) -> Result<(), String> {
+    pub fn end_frame(&mut self) -> Result<(), GsdError> {
         debug!("end frame: {}", self.name);
+        self.check_writable("end frames of")?;
 
         let retval = unsafe { libgsd::gsd_end_frame(&mut self.handle as *mut libgsd::gsd_handle) };
-        check_gsd_errors(retval, &self.name)
+        self.refresh_nframes();
+        self.clear_cache();
+        check_gsd_errors(retval, &self.name)?;
+        self.frame_chunks.clear();
+        Ok(())
+    }
+
+    /// Write every chunk in `chunks`, then end the frame. The mode, duplicate names and shapes
+    /// are checked before anything is written. GSD cannot discard chunks already added to the
+    /// current frame, so when a write still fails, the chunks written before it remain pending
+    /// and become part of the frame ended next. Call `end_frame` or `truncate` to deal with them.
+    pub fn write_frame(&mut self, chunks: &[(&str, ChunkData)]) -> Result<(), GsdError> {
+        self.check_writable("write chunks to")?;
+        let mut names = HashSet::new();
+        for (name, data) in chunks {
+            if !names.insert(*name) || self.frame_chunks.contains(*name) {
+                return Err(GsdError::InvalidArgument(format!(
+                    "chunk {} was already written to the current frame of: {}",
+                    name, self.name
+                )));
+            }
+            if data.dim().1 == 0 {
+                return Err(GsdError::InvalidArgument(format!(
+                    "chunk {} must have at least one column",
+                    name
+                )));
+            }
+        }
+
+        for (name, data) in chunks {
+            data.write_to(self, name)?;
+        }
+        self.end_frame()
+    }
+
+    /// Collect the chunks of a frame with `FrameBuilder::chunk`, to be written together by
+    /// `FrameBuilder::finish`.
+    pub fn begin_frame(&mut self) -> FrameBuilder<'_, '_> {
+        FrameBuilder {
+            file: self,
+            chunks: Vec::new(),
+        }
+    }
+
+    /// Start writing a frame through a guard that ends the frame when it goes out of scope.
+    pub fn frame(&mut self) -> FrameWriter<'_> {
+        FrameWriter {
+            file: self,
+            committed: false,
+        }
+    }
+
+    fn find_chunk(&self, frame: usize, name: &str) -> Option<&libgsd::gsd_index_entry> {
+        let cached = self
+            .index_cache
+            .borrow()
+            .get(name)
+            .and_then(|frames| frames.get(&frame))
+            .copied();
+        let index_entry = cached.unwrap_or_else(|| {
+            let c_name = CString::new(name).expect("CString::new failed");
+            let index_entry = unsafe {
+                libgsd::gsd_find_chunk(
+                    &self.handle as *const libgsd::gsd_handle,
+                    frame as u64,
+                    c_name.as_ptr(),
+                )
+            };
+            let mut cache = self.index_cache.borrow_mut();
+            if !cache.contains_key(name) && cache.len() >= INDEX_CACHE_CAPACITY {
+                cache.clear();
+            }
+            let frames = cache.entry(name.to_owned()).or_default();
+            if frames.len() >= INDEX_CACHE_CAPACITY {
+                frames.clear();
+            }
+            frames.insert(frame, index_entry);
+            index_entry
+        });
+        // the index only changes through `&mut self`, which clears the cache
+        unsafe { index_entry.as_ref() }
+    }
+
+    /// Forget the index entries memoized by chunk lookups. The cache is cleared automatically
+    /// whenever frames are added or removed, so this is only needed to free its memory.
+    pub fn clear_cache(&mut self) {
+        self.index_cache.get_mut().clear();
+    }
+
+    /// Fail with `GsdError::FrameOutOfRange` unless `frame` is a committed frame, so that a
+    /// missing chunk of an existing frame can be told apart from a frame that does not exist.
+    fn check_frame(&self, frame: usize) -> Result<(), GsdError> {
+        if frame < self.nframes {
+            Ok(())
+        } else {
+            Err(GsdError::FrameOutOfRange {
+                frame,
+                nframes: self.nframes,
+            })
+        }
     }
 
     pub fn chunk_exists(&self, frame: usize, name: &str) -> bool {
-        let c_name = CString::new(name).expect("CString::new failed");
-        let index_entry = unsafe {
-            libgsd::gsd_find_chunk(
-                &self.handle as *const libgsd::gsd_handle,
-                frame as u64,
-                c_name.as_ptr(),
-            )
-        };
-        !index_entry.is_null()
+        // corrupt entries exist, reading them reports the corruption
+        !matches!(self.chunk_lookup(frame, name), Ok(None))
+    }
+
+    /// Type and shape of chunk `name` in `frame`, or `None` when the frame does not store it,
+    /// with a single index lookup. Entries with an invalid type are reported as
+    /// `GsdError::FileCorrupt`, like `chunk_info` does.
+    pub fn chunk_lookup(&self, frame: usize, name: &str) -> Result<Option<ChunkInfo>, GsdError> {
+        match self.find_chunk(frame, name) {
+            Some(index_entry) => Ok(Some(ChunkInfo {
+                type_: entry_type(index_entry, &self.name)?,
+                n: index_entry.N,
+                m: index_entry.M,
+                flags: index_entry.flags,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether a chunk is stored in `frame` or, failing that, in frame 0. HOOMD readers treat
+    /// chunks stored only in frame 0 as present in every later frame.
+    pub fn chunk_exists_effective(&self, frame: usize, name: &str) -> bool {
+        self.chunk_exists(frame, name) || self.chunk_exists(0, name)
+    }
+
+    pub fn chunk_info(&self, frame: usize, name: &str) -> Result<ChunkInfo, GsdError> {
+        self.check_frame(frame)?;
+        if let Some(index_entry) = self.find_chunk(frame, name) {
+            Ok(ChunkInfo {
+                type_: entry_type(index_entry, &self.name)?,
+                n: index_entry.N,
+                m: index_entry.M,
+                flags: index_entry.flags,
+            })
+        } else {
+            Err(GsdError::ChunkNotFound {
+                frame,
+                name: name.to_owned(),
+            })
+        }
+    }
+
+    /// Names of all chunks stored explicitly in `frame`.
+    pub fn chunk_names(&self, frame: usize) -> Vec<String> {
+        self.find_matching_chunk_names("")
+            .into_iter()
+            .filter(|name| self.chunk_exists(frame, name))
+            .map(|name| name.to_owned())
+            .collect()
+    }
+
+    /// Compare the chunks stored explicitly in frames `a` and `b`, with one entry for each
+    /// chunk stored in either frame. Chunks that a frame inherits from frame 0 count as absent.
+    pub fn diff_frames(&self, a: usize, b: usize) -> Result<Vec<ChunkDiff>, GsdError> {
+        self.check_readable()?;
+        self.check_frame(a)?;
+        self.check_frame(b)?;
+        let mut diffs = Vec::new();
+        for name in self.find_matching_chunk_names("") {
+            let present_in_a = self.chunk_exists(a, name);
+            let present_in_b = self.chunk_exists(b, name);
+            if !present_in_a && !present_in_b {
+                continue;
+            }
+            let bytes_equal = present_in_a
+                && present_in_b
+                && self.read_chunk_bytes(a, name)? == self.read_chunk_bytes(b, name)?;
+            diffs.push(ChunkDiff {
+                name: name.to_owned(),
+                present_in_a,
+                present_in_b,
+                bytes_equal,
+            });
+        }
+        Ok(diffs)
+    }
+
+    /// Content hash of the chunks stored explicitly in `frame`: each chunk name, type, shape
+    /// and payload, in sorted name order. The hash only depends on the contents, not on where
+    /// they are stored, and is stable across runs and platforms, so frames with equal hashes can
+    /// be treated as duplicates. Chunks inherited from frame 0 are not included.
+    pub fn frame_hash(&self, frame: usize) -> Result<u64, GsdError> {
+        self.check_readable()?;
+        self.check_frame(frame)?;
+        let mut names = self.chunk_names(frame);
+        names.sort();
+        let mut hasher = Fnv1a::new();
+        for name in names {
+            let (data, type_, (n, m)) = self.read_chunk_bytes(frame, &name)?;
+            // the separator keeps the name from running into the rest of the chunk
+            hasher.update(name.as_bytes());
+            hasher.update(&[0, type_ as u8]);
+            hasher.update(&n.to_le_bytes());
+            hasher.update(&m.to_le_bytes());
+            hasher.update(&data);
+        }
+        Ok(hasher.0)
+    }
+
+    /// Iterate lazily over every chunk of every frame, with its type and shape, in frame order.
+    /// Chunks whose type is invalid are skipped; `chunk_info` reports them as corrupt.
+    pub fn chunks(&self) -> impl Iterator<Item = (usize, String, ChunkInfo)> + '_ {
+        (0..self.nframes).flat_map(move |frame| {
+            self.chunk_names(frame).into_iter().filter_map(move |name| {
+                let info = self.chunk_info(frame, &name).ok()?;
+                Some((frame, name, info))
+            })
+        })
+    }
+
+    /// Count, for every chunk name, the frames that store the chunk themselves rather than
+    /// inheriting it from frame 0. Static data written to every frame shows up with a count of
+    /// `nframes`.
+    pub fn chunk_frequency(&self) -> HashMap<String, usize> {
+        let mut frequency = HashMap::new();
+        for (_, name, _) in self.chunks() {
+            *frequency.entry(name).or_insert(0) += 1;
+        }
+        frequency
     }
 
     pub fn read_chunk<T: Clone + num_traits::Num>(
         &self,
         frame: usize,
         name: &str,
-    ) -> Result<Array2<T>, String> {
-        let c_name = CString::new(name).expect("CString::new failed");
-        if let Some(index_entry) = unsafe {
-            libgsd::gsd_find_chunk(
+    ) -> Result<Array2<T>, GsdError> {
+        self.check_readable()?;
+        let info = self.chunk_info(frame, name)?;
+        info.type_.check_match::<T>()?;
+
+        let mut data = Array2::<T>::zeros((info.n as usize, info.m as usize));
+        self.read_chunk_into(frame, name, &mut data.view_mut())?;
+
+        Ok(data)
+    }
+
+    /// Read the single element of a chunk of shape `(1, 1)`, such as `configuration/step`.
+    /// Chunks of any other shape are rejected with `GsdError::InvalidArgument`.
+    pub fn read_chunk_scalar<T: Clone + num_traits::Num>(
+        &self,
+        frame: usize,
+        name: &str,
+    ) -> Result<T, GsdError> {
+        self.check_readable()?;
+        let info = self.chunk_info(frame, name)?;
+        if (info.n, info.m) != (1, 1) {
+            return Err(GsdError::InvalidArgument(format!(
+                "chunk {} of shape {:?} is not a scalar",
+                name,
+                (info.n, info.m)
+            )));
+        }
+        let data = self.read_chunk::<T>(frame, name)?;
+        Ok(data[[0, 0]].clone())
+    }
+
+    /// Read a chunk with `I` columns as one `[T; I]` per row, e.g. `I = 3` for positions.
+    /// The column count comes from the file, so a mismatch is reported as
+    /// `GsdError::InvalidArgument` rather than a panic.
+    pub fn read_chunk_with_dim<T: Clone + num_traits::Num, const I: usize>(
+        &self,
+        frame: usize,
+        name: &str,
+    ) -> Result<Vec<[T; I]>, GsdError> {
+        let data = self.read_chunk::<T>(frame, name)?;
+        if data.ncols() != I {
+            return Err(GsdError::InvalidArgument(format!(
+                "expected {} columns but chunk {} in frame {} has {}",
+                I,
+                name,
+                frame,
+                data.ncols()
+            )));
+        }
+        Ok(data
+            .outer_iter()
+            .map(|row| std::array::from_fn(|j| row[j].clone()))
+            .collect())
+    }
+
+    /// Read chunk `name` from every frame and stack the chunks into an array of shape
+    /// `(nframes, N, M)`. The chunk must be stored in every frame with the same shape, otherwise
+    /// this fails with `GsdError::ChunkNotFound` or `GsdError::InvalidArgument`.
+    pub fn read_chunk_series<T: Clone + num_traits::Num>(
+        &self,
+        name: &str,
+    ) -> Result<Array3<T>, GsdError> {
+        self.check_readable()?;
+        if self.nframes == 0 {
+            return Ok(Array3::zeros((0, 0, 0)));
+        }
+        let first = self.chunk_info(0, name)?;
+        let shape = (first.n as usize, first.m as usize);
+        let mut series = Array3::<T>::zeros((self.nframes, shape.0, shape.1));
+        for (frame, mut out) in series.outer_iter_mut().enumerate() {
+            let info = self.chunk_info(frame, name)?;
+            if (info.n, info.m) != (first.n, first.m) {
+                return Err(GsdError::InvalidArgument(format!(
+                    "shape {:?} of chunk {} in frame {} differs from shape {:?} in frame 0",
+                    (info.n, info.m),
+                    name,
+                    frame,
+                    shape
+                )));
+            }
+            self.read_chunk_into(frame, name, &mut out)?;
+        }
+        Ok(series)
+    }
+
+    /// Read a chunk into a caller-supplied buffer, so that repeated reads of same-sized chunks
+    /// can reuse one allocation. `out` must be contiguous and match the `(N, M)` shape of the
+    /// chunk.
+    pub fn read_chunk_into<T: Clone + num_traits::Num>(
+        &self,
+        frame: usize,
+        name: &str,
+        out: &mut ArrayViewMut2<T>,
+    ) -> Result<(), GsdError> {
+        self.check_readable()?;
+        self.check_frame(frame)?;
+        if let Some(index_entry) = self.find_chunk(frame, name) {
+            let gsd_type = entry_type(index_entry, &self.name)?;
+            gsd_type.check_match::<T>()?;
+
+            let shape = (index_entry.N as usize, index_entry.M as usize);
+            if out.dim() != shape {
+                return Err(GsdError::InvalidArgument(format!(
+                    "output shape {:?} does not match shape {:?} of chunk {}",
+                    out.dim(),
+                    shape,
+                    name
+                )));
+            }
+            let out = out.as_slice_mut().ok_or_else(|| {
+                GsdError::InvalidArgument(format!(
+                    "output buffer for chunk {} must be contiguous",
+                    name
+                ))
+            })?;
+            self.read_entry_into(index_entry, out)
+        } else {
+            Err(GsdError::ChunkNotFound {
+                frame,
+                name: name.to_owned(),
+            })
+        }
+    }
+
+    /// Read the data of `index_entry`, whose type must be `T`, into `out`, which must hold its
+    /// `N * M` elements.
+    fn read_entry_into<T>(
+        &self,
+        index_entry: &libgsd::gsd_index_entry,
+        out: &mut [T],
+    ) -> Result<(), GsdError> {
+        // gsd_read_chunk treats zero-sized chunks as corrupt, but there is nothing to read
+        if out.is_empty() {
+            return Ok(());
+        }
+
+        let retval = unsafe {
+            libgsd::gsd_read_chunk(
                 &self.handle as *const libgsd::gsd_handle,
-                frame as u64,
-                c_name.as_ptr(),
+                out.as_mut_ptr() as *mut c_void,
+                index_entry as *const libgsd::gsd_index_entry,
             )
-            .as_ref()
-        } {
-            let gsd_type: GSDType = unsafe { ::std::mem::transmute(index_entry.type_) };
-            gsd_type.check_match::<T>()?;
-            let data = Array2::<T>::zeros((index_entry.N as usize, index_entry.M as usize));
+        };
+        check_gsd_errors(retval, &self.name)?;
 
-            let retval = unsafe {
-                libgsd::gsd_read_chunk(
-                    &self.handle as *const libgsd::gsd_handle,
-                    data.as_ptr() as *mut c_void,
-                    index_entry as *const libgsd::gsd_index_entry,
-                )
+        // GSD files are little-endian, the check compiles away on little-endian hosts. Raw byte
+        // reads (`T = u8`) are left in the order they are stored in.
+        if cfg!(target_endian = "big") {
+            let size = std::mem::size_of::<T>();
+            let bytes = unsafe {
+                std::slice::from_raw_parts_mut(out.as_mut_ptr() as *mut u8, out.len() * size)
             };
+            swap_element_bytes(bytes, size);
+        }
+        Ok(())
+    }
+
+    /// Read the payload of a chunk as raw bytes in the little-endian order stored in the file,
+    /// whatever its type, and return it with the stored type and `(N, M)` shape.
+    pub fn read_chunk_bytes(
+        &self,
+        frame: usize,
+        name: &str,
+    ) -> Result<(Vec<u8>, GSDType, (u64, u32)), GsdError> {
+        self.check_readable()?;
+        self.check_frame(frame)?;
+        let index_entry = self
+            .find_chunk(frame, name)
+            .ok_or_else(|| GsdError::ChunkNotFound {
+                frame,
+                name: name.to_owned(),
+            })?;
+        let gsd_type = entry_type(index_entry, &self.name)?;
+        let mut data =
+            vec![0u8; index_entry.N as usize * index_entry.M as usize * gsd_type.size_bytes()];
+        self.read_entry_into(index_entry, &mut data)?;
+        Ok((data, gsd_type, (index_entry.N, index_entry.M)))
+    }
 
-            check_gsd_errors(retval, &self.name)?;
+    /// Handle for reading chunk `name` as `T` from many frames, see `TypedChunk`.
+    pub fn typed_chunk<T: Clone + num_traits::Num>(
+        &self,
+        name: &str,
+    ) -> Result<TypedChunk<T>, GsdError> {
+        Ok(TypedChunk {
+            name: name.to_owned(),
+            c_name: CString::new(name).expect("CString::new failed"),
+            type_: GSDType::try_from_type::<T>()?,
+            _data: PhantomData,
+        })
+    }
 
-            Ok(data)
-        } else {
-            Err(format!(
-                "frame {} / chunk {} not found in: {}",
-                frame, name, self.name
-            ))
+    /// Read a chunk stored with any numeric type and convert each element to `T`. Unlike
+    /// `read_chunk`, the stored type does not have to match `T` exactly:
+    /// * integer to integer conversions are allowed as long as every value fits in `T`,
+    /// * integer to floating point conversions are allowed, rounding values that `T` cannot
+    ///   represent exactly,
+    /// * floating point conversions are allowed, rounding to nearest when narrowing `f64` to
+    ///   `f32`,
+    /// * floating point to integer conversions are rejected with `GsdError::TypeMismatch`.
+    pub fn read_chunk_as<T: Clone + num_traits::Num + num_traits::NumCast>(
+        &self,
+        frame: usize,
+        name: &str,
+    ) -> Result<Array2<T>, GsdError> {
+        self.check_readable()?;
+        let info = self.chunk_info(frame, name)?;
+        let target = GSDType::try_from_type::<T>()?;
+        if info.type_ == target {
+            return self.read_chunk(frame, name);
+        }
+        if info.type_.is_float() && !target.is_float() {
+            return Err(GsdError::TypeMismatch {
+                expected: target,
+                found: info.type_,
+            });
+        }
+
+        match info.type_ {
+            GSDType::UINT8 => self.read_chunk_cast::<u8, T>(frame, name),
+            GSDType::UINT16 => self.read_chunk_cast::<u16, T>(frame, name),
+            GSDType::UINT32 => self.read_chunk_cast::<u32, T>(frame, name),
+            GSDType::UINT64 => self.read_chunk_cast::<u64, T>(frame, name),
+            GSDType::INT8 => self.read_chunk_cast::<i8, T>(frame, name),
+            GSDType::INT16 => self.read_chunk_cast::<i16, T>(frame, name),
+            GSDType::INT32 => self.read_chunk_cast::<i32, T>(frame, name),
+            GSDType::INT64 => self.read_chunk_cast::<i64, T>(frame, name),
+            GSDType::FLOAT => self.read_chunk_cast::<f32, T>(frame, name),
+            GSDType::DOUBLE => self.read_chunk_cast::<f64, T>(frame, name),
+        }
+    }
+
+    /// Copy chunk `name` of `frame` into the current frame of `dest`, keeping its stored type.
+    pub fn copy_chunk_to(
+        &self,
+        frame: usize,
+        name: &str,
+        dest: &mut GSDFile,
+    ) -> Result<(), GsdError> {
+        copy_chunk(self, frame, dest, name)
+    }
+
+    /// Write chunk `name` of `frame` to `out` as CSV, with one line per row of the chunk and
+    /// its `M` columns separated by commas. Floats are always written with a decimal point or
+    /// exponent, so float columns stay distinguishable from integer ones.
+    pub fn write_chunk_csv<W: Write>(
+        &self,
+        frame: usize,
+        name: &str,
+        mut out: W,
+    ) -> Result<(), GsdError> {
+        let out = &mut out;
+        let written = match self.chunk_info(frame, name)?.type_ {
+            GSDType::UINT8 => write_csv(&self.read_chunk::<u8>(frame, name)?, out, u8::to_string),
+            GSDType::UINT16 => {
+                write_csv(&self.read_chunk::<u16>(frame, name)?, out, u16::to_string)
+            }
+            GSDType::UINT32 => {
+                write_csv(&self.read_chunk::<u32>(frame, name)?, out, u32::to_string)
+            }
+            GSDType::UINT64 => {
+                write_csv(&self.read_chunk::<u64>(frame, name)?, out, u64::to_string)
+            }
+            GSDType::INT8 => write_csv(&self.read_chunk::<i8>(frame, name)?, out, i8::to_string),
+            GSDType::INT16 => write_csv(&self.read_chunk::<i16>(frame, name)?, out, i16::to_string),
+            GSDType::INT32 => write_csv(&self.read_chunk::<i32>(frame, name)?, out, i32::to_string),
+            GSDType::INT64 => write_csv(&self.read_chunk::<i64>(frame, name)?, out, i64::to_string),
+            GSDType::FLOAT => write_csv(&self.read_chunk::<f32>(frame, name)?, out, |x| {
+                format!("{:?}", x)
+            }),
+            GSDType::DOUBLE => write_csv(&self.read_chunk::<f64>(frame, name)?, out, |x| {
+                format!("{:?}", x)
+            }),
+        };
+        written.map_err(|e| GsdError::IO(format!("writing chunk {} as CSV: {}", name, e)))
+    }
+
+    /// Read a chunk stored with any numeric type as `f32`, e.g. integer image flags for display.
+    /// Equivalent to `read_chunk_as::<f32>`, which never rejects a conversion to `f32`.
+    pub fn read_chunk_f32(&self, frame: usize, name: &str) -> Result<Array2<f32>, GsdError> {
+        self.read_chunk_as::<f32>(frame, name)
+    }
+
+    fn read_chunk_cast<S, T>(&self, frame: usize, name: &str) -> Result<Array2<T>, GsdError>
+    where
+        S: Clone + num_traits::Num + num_traits::ToPrimitive,
+        T: Clone + num_traits::Num + num_traits::NumCast,
+    {
+        let data = self.read_chunk::<S>(frame, name)?;
+        let mut output = Array2::<T>::zeros(data.raw_dim());
+        for (out, value) in output.iter_mut().zip(data.iter()) {
+            *out = <T as num_traits::NumCast>::from(value.clone()).ok_or_else(|| {
+                GsdError::InvalidArgument(format!(
+                    "value in chunk {} does not fit in {:?}",
+                    name,
+                    std::any::type_name::<T>()
+                ))
+            })?;
         }
+        Ok(output)
     }
 
     pub fn read_chunk_dyn<T: Clone + num_traits::Num>(
         &self,
         frame: usize,
         name: &str,
-    ) -> Result<ArrayD<T>, String> {
+    ) -> Result<ArrayD<T>, GsdError> {
         let data = self.read_chunk(frame, name)?;
         Ok(data.into_dyn())
     }
@@ -272,48 +1325,176 @@ impl GSDFile {
         &self,
         frame: usize,
         name: &str,
-    ) -> Result<Array1<T>, String> {
+    ) -> Result<Array1<T>, GsdError> {
         let data = self.read_chunk::<T>(frame, name)?;
         let len = data.len();
         match data.into_shape(len) {
             Ok(data) => Ok(data),
-            Err(e) => Err(format!("{}", e)),
+            Err(e) => Err(GsdError::InvalidArgument(format!("{}", e))),
         }
     }
 
+    /// Read an array written by `write_chunk_nd`, restoring its original shape from the
+    /// `{name}/shape` chunk.
+    pub fn read_chunk_nd<T: Clone + num_traits::Num>(
+        &self,
+        frame: usize,
+        name: &str,
+    ) -> Result<ArrayD<T>, GsdError> {
+        let shape: Vec<usize> = self
+            .read_chunk_flat::<u64>(frame, &format!("{}/shape", name))?
+            .iter()
+            .map(|&d| d as usize)
+            .collect();
+        let data = self.read_chunk::<T>(frame, name)?;
+        data.into_shape(shape)
+            .map_err(|e| GsdError::FileCorrupt(format!("{}: {}", name, e)))
+    }
+
+    /// Read a chunk of strings written by `write_string_chunk`, trimming the trailing NULs of
+    /// each row.
+    pub fn read_string_chunk(&self, frame: usize, name: &str) -> Result<Vec<String>, GsdError> {
+        let data = self.read_chunk::<i8>(frame, name)?;
+        Ok(data
+            .outer_iter()
+            .map(|row| {
+                let bytes: Vec<u8> = row.iter().map(|&c| c as u8).collect();
+                String::from_utf8_lossy(&bytes)
+                    .trim_end_matches('\0')
+                    .to_owned()
+            })
+            .collect())
+    }
+
     pub fn find_matching_chunk_names(&self, pattern: &str) -> Vec<&str> {
         let mut result = Vec::<&str>::new();
         let c_pattern = CString::new(pattern).expect("CString::new failed");
         let null_ptr: *const i8 = ptr::null();
 
         let mut c_found = unsafe {
-            CStr::from_ptr(libgsd::gsd_find_matching_chunk_name(
+            libgsd::gsd_find_matching_chunk_name(
                 &self.handle as *const libgsd::gsd_handle,
                 c_pattern.as_ptr(),
                 null_ptr,
-            ))
+            )
         };
 
-        while !c_found.as_ptr().is_null() {
-            result.push(c_found.to_str().unwrap());
+        // the C API signals the end of the search with a null pointer, which must be
+        // checked before it is handed to CStr
+        while !c_found.is_null() {
+            result.push(unsafe { CStr::from_ptr(c_found) }.to_str().unwrap());
             c_found = unsafe {
-                CStr::from_ptr(libgsd::gsd_find_matching_chunk_name(
+                libgsd::gsd_find_matching_chunk_name(
                     &self.handle as *const libgsd::gsd_handle,
                     c_pattern.as_ptr(),
-                    c_found.as_ptr(),
-                ))
+                    c_found,
+                )
             };
         }
 
         return result;
     }
 
-    pub fn upgrade(&mut self) -> Result<(), String> {
+    /// Check the file index for inconsistencies that `gsd_open` does not catch: chunks with an
+    /// invalid type, chunks whose data would extend past the end of the file, as left behind by
+    /// a truncated copy, and frames that store more than one chunk with the same name. Returns
+    /// `GsdError::FileCorrupt` naming the first offending chunk.
+    pub fn validate(&self) -> Result<(), GsdError> {
+        self.validate_with_progress(|_, _| {})
+    }
+
+    /// Like [`GSDFile::validate`], calling `progress` with `(processed, total)` frames after
+    /// the chunks of each frame have been checked.
+    pub fn validate_with_progress(
+        &self,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<(), GsdError> {
+        let total = self.nframes();
+        let mut processed = 0;
+        // resolve the name of an entry through the index, as find_chunk does, rather than
+        // relying on the order of the namelist
+        let names = self.find_matching_chunk_names("");
+        let name_of = |index_entry: &libgsd::gsd_index_entry| {
+            names
+                .iter()
+                .find(|name| {
+                    self.find_chunk(index_entry.frame as usize, name)
+                        .map(|entry| entry.id)
+                        == Some(index_entry.id)
+                })
+                .copied()
+                .unwrap_or("<unknown>")
+        };
+        // the handle tracks the size including data still held in the write buffer
+        let file_size = self.handle.file_size as u64;
+        let mut seen = HashSet::new();
+        for index_entry in self.index_entries() {
+            // entries are sorted by frame, so every earlier frame has been checked
+            while processed < (index_entry.frame as usize).min(total) {
+                processed += 1;
+                progress(processed, total);
+            }
+            let info = ChunkInfo {
+                type_: entry_type(index_entry, &self.name)?,
+                n: index_entry.N,
+                m: index_entry.M,
+                flags: index_entry.flags,
+            };
+            let end = index_entry.location as u64 + info.nbytes() as u64;
+            if index_entry.location < 0 || end > file_size {
+                return Err(GsdError::FileCorrupt(format!(
+                    "chunk {} of frame {} ends at byte {}, past the end of the file at byte {}: {}",
+                    name_of(index_entry),
+                    index_entry.frame,
+                    end,
+                    file_size,
+                    self.name
+                )));
+            }
+            if !seen.insert((index_entry.frame, index_entry.id)) {
+                return Err(GsdError::FileCorrupt(format!(
+                    "frame {} stores chunk {} more than once in: {}",
+                    index_entry.frame,
+                    name_of(index_entry),
+                    self.name
+                )));
+            }
+        }
+        while processed < total {
+            processed += 1;
+            progress(processed, total);
+        }
+        Ok(())
+    }
+
+    /// Entries of the index of every committed frame.
+    fn index_entries(&self) -> &[libgsd::gsd_index_entry] {
+        let file_index = &self.handle.file_index;
+        if file_index.data.is_null() {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(file_index.data, file_index.size as usize) }
+        }
+    }
+
+    /// Whether the file was written with an older version of the file layer than
+    /// `CURRENT_GSD_VERSION`, so that `upgrade` would rewrite its index.
+    pub fn needs_upgrade(&self) -> bool {
+        self.gsd_version() < CURRENT_GSD_VERSION
+    }
+
+    /// Upgrade the file to the file layer version of the linked library in place and return
+    /// whether its version changed. Requires a writable handle and fails with
+    /// `GsdError::NotWritable` otherwise.
+    pub fn upgrade(&mut self) -> Result<bool, GsdError> {
+        self.check_writable("upgrade")?;
+        let before = self.gsd_version();
         let retval = unsafe { libgsd::gsd_upgrade(&mut self.handle as *mut libgsd::gsd_handle) };
+        self.clear_cache();
 
         check_gsd_errors(retval, &self.name)?;
 
-        Ok(())
+        Ok(self.gsd_version() != before)
     }
 
     pub fn name(&self) -> &str {
@@ -321,7 +1502,57 @@ impl GSDFile {
     }
 
     pub fn mode(&self) -> &str {
-        &self.mode
+        self.mode.as_str()
+    }
+
+    /// Whether the file was opened in a mode that allows writing frames.
+    pub fn is_writable(&self) -> bool {
+        self.mode != Mode::Read
+    }
+
+    /// Whether the file was opened in a mode that allows reading chunks. The write-only modes
+    /// `"wb"`, `"xb"` and `"ab"` do not load the index of the file.
+    pub fn is_readable(&self) -> bool {
+        !matches!(self.mode.open_flag(), OpenFlag::Append)
+    }
+
+    /// Fail with `GsdError::NotWritable` before calling into the C API on a read-only file.
+    fn check_writable(&self, action: &str) -> Result<(), GsdError> {
+        if self.is_writable() {
+            Ok(())
+        } else {
+            Err(GsdError::NotWritable(format!(
+                "cannot {} a file opened with mode {}: {}",
+                action,
+                self.mode(),
+                self.name
+            )))
+        }
+    }
+
+    /// Fail with `GsdError::NotReadable` before calling into the C API on a write-only file.
+    fn check_readable(&self) -> Result<(), GsdError> {
+        if self.is_readable() {
+            Ok(())
+        } else {
+            Err(GsdError::NotReadable(format!(
+                "cannot read chunks of a file opened with mode {}: {}",
+                self.mode(),
+                self.name
+            )))
+        }
+    }
+
+    /// All header fields at once.
+    pub fn header(&self) -> Header {
+        Header {
+            application: self.application().to_owned(),
+            schema: self.schema().to_owned(),
+            schema_version: self.schema_version(),
+            gsd_version: self.gsd_version(),
+            raw_schema_version: self.handle.header.schema_version,
+            raw_gsd_version: self.handle.header.gsd_version,
+        }
     }
 
     pub fn gsd_version(&self) -> (u32, u32) {
@@ -335,16 +1566,34 @@ impl GSDFile {
     }
 
     pub fn schema(&self) -> &str {
-        let schema = self.handle.header.schema;
+        let schema = &self.handle.header.schema;
         return unsafe { CStr::from_ptr(schema.as_ptr()).to_str().unwrap() };
     }
 
     pub fn application(&self) -> &str {
-        let application = self.handle.header.application;
+        let application = &self.handle.header.application;
         return unsafe { CStr::from_ptr(application.as_ptr()).to_str().unwrap() };
     }
 }
 
+impl fmt::Debug for GSDFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the C handle holds raw pointers and buffers, so only the header is shown
+        f.debug_struct("GSDFile")
+            .field("name", &self.name)
+            .field("mode", &self.mode())
+            .field("schema", &self.schema())
+            .field("schema_version", &self.schema_version())
+            .field("application", &self.application())
+            .field("nframes", &self.nframes)
+            .finish()
+    }
+}
+
+// The C library keeps no global or thread-local state, and every pointer in the handle and
+// the index cache refers to memory owned by this handle, so it may move between threads.
+unsafe impl Send for GSDFile {}
+
 impl Drop for GSDFile {
     fn drop(&mut self) {
         debug!("Closing file: {}", self.name);
@@ -353,20 +1602,354 @@ impl Drop for GSDFile {
     }
 }
 
+/// Chunks of a frame collected by reference, created with `GSDFile::begin_frame`. Nothing is
+/// written until `finish`, which checks all chunks first like `GSDFile::write_frame`. Dropping
+/// the builder without finishing writes nothing.
+pub struct FrameBuilder<'f, 'a> {
+    file: &'f mut GSDFile,
+    chunks: Vec<(&'a str, ChunkData<'a>)>,
+}
+
+impl<'f, 'a> FrameBuilder<'f, 'a> {
+    pub fn chunk<D: Into<ChunkData<'a>>>(mut self, name: &'a str, data: D) -> Self {
+        self.chunks.push((name, data.into()));
+        self
+    }
+
+    /// Write the collected chunks and end the frame.
+    pub fn finish(self) -> Result<(), GsdError> {
+        self.file.write_frame(&self.chunks)
+    }
+}
+
+/// Builder for opening a `GSDFile`, naming the options that `try_new` takes positionally.
+/// The default mode is `Mode::Read`.
+#[derive(Debug, Clone, Default)]
+pub struct GSDFileBuilder {
+    mode: Mode,
+    application: Option<String>,
+    schema: Option<String>,
+    schema_version: Option<(u32, u32)>,
+}
+
+impl GSDFileBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn application(mut self, application: &str) -> Self {
+        self.application = Some(application.to_owned());
+        self
+    }
+
+    pub fn schema(mut self, schema: &str) -> Self {
+        self.schema = Some(schema.to_owned());
+        self
+    }
+
+    pub fn schema_version(mut self, major: u32, minor: u32) -> Self {
+        self.schema_version = Some((major, minor));
+        self
+    }
+
+    /// Open the file at `path`. Modes that create a new file require the application, schema
+    /// and schema version to be set, which is checked before the file is touched.
+    pub fn open<P: AsRef<Path>>(self, path: P) -> Result<GSDFile, GsdError> {
+        let metadata = match (self.application, self.schema, self.schema_version) {
+            (Some(application), Some(schema), Some(schema_version)) => Some(Metadata {
+                application,
+                schema,
+                schema_version,
+            }),
+            (application, schema, schema_version) if self.mode.overwrite() => {
+                let missing: Vec<&str> = [
+                    (application.is_none(), "application"),
+                    (schema.is_none(), "schema"),
+                    (schema_version.is_none(), "schema_version"),
+                ]
+                .iter()
+                .filter(|(missing, _)| *missing)
+                .map(|(_, field)| *field)
+                .collect();
+                return Err(GsdError::InvalidArgument(format!(
+                    "mode {} creates a new file, but {} is not set: {}",
+                    self.mode.as_str(),
+                    missing.join(", "),
+                    path.as_ref().display()
+                )));
+            }
+            _ => None,
+        };
+
+        GSDFile::open(path, self.mode, metadata)
+    }
+}
+
+/// Writes the chunks of one frame and ends the frame when dropped, so that chunks cannot
+/// leak into the next frame. Errors from ending the frame on drop are only logged; call
+/// `commit` to handle them.
+pub struct FrameWriter<'f> {
+    file: &'f mut GSDFile,
+    committed: bool,
+}
+
+impl<'f> FrameWriter<'f> {
+    /// See `GSDFile::write_chunk`.
+    pub fn write_chunk<'a, T, D, const I: usize>(
+        &mut self,
+        name: &str,
+        data: D,
+    ) -> Result<(), GsdError>
+    where
+        D: ChunkSource<'a, T, Dim<[usize; I]>>,
+        T: 'a + Clone + num_traits::Num,
+        Dim<[usize; I]>: Dimension,
+    {
+        self.file.write_chunk(name, data)
+    }
+
+    /// End the frame.
+    pub fn commit(mut self) -> Result<(), GsdError> {
+        self.committed = true;
+        self.file.end_frame()
+    }
+}
+
+impl Drop for FrameWriter<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            if let Err(err) = self.file.end_frame() {
+                error!("failed to end frame on drop: {}", err);
+            }
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! open {
     ($name:expr, $mode:expr) => {
-        $crate::fl::GSDFile::try_new($name.to_owned(), $mode.to_owned(), None, None, None)
-    };
-    ($name:expr, $mode:expr, $app:expr, $schema:expr, $schema_ver:expr) => {
-        $crate::fl::GSDFile::try_new(
-            $name.to_owned(),
-            $mode.to_owned(),
-            Some($app.to_owned()),
-            Some($schema.to_owned()),
-            Some($schema_ver),
-        )
+        $mode
+            .parse::<$crate::fl::Mode>()
+            .and_then(|mode| $crate::fl::GSDFileBuilder::new().mode(mode).open($name))
     };
+    ($name:expr, $mode:expr, $app:expr, $schema:expr, $schema_ver:expr) => {{
+        let (major, minor) = $schema_ver;
+        $mode.parse::<$crate::fl::Mode>().and_then(|mode| {
+            $crate::fl::GSDFileBuilder::new()
+                .mode(mode)
+                .application(&$app)
+                .schema(&$schema)
+                .schema_version(major, minor)
+                .open($name)
+        })
+    }};
 }
 
 pub use open;
+
+/// Copy chunk `name` of frame `src_frame` of `src` into the current frame of `dst`. The
+/// payload is copied as raw bytes, so the chunk keeps its type and `(N, M)` shape without the
+/// caller naming its Rust type.
+pub fn copy_chunk(
+    src: &GSDFile,
+    src_frame: usize,
+    dst: &mut GSDFile,
+    name: &str,
+) -> Result<(), GsdError> {
+    dst.check_writable("write chunks to")?;
+    let (data, gsd_type, (n, m)) = src.read_chunk_bytes(src_frame, name)?;
+    // the buffer holds exactly the n * m elements read from the source
+    unsafe { dst.write_raw_chunk(name, gsd_type, n, m, data.as_ptr() as *const c_void) }
+}
+
+/// Copy every chunk stored in frame `src_frame` of `src` into the current frame of `dst`,
+/// renaming the chunks found in `rename` and keeping the names of the others. Like
+/// `copy_chunk`, types and shapes are preserved. The frame of `dst` is left open, so more chunks
+/// can be added before calling `end_frame`.
+pub fn copy_frame_remapped(
+    src: &GSDFile,
+    src_frame: usize,
+    dst: &mut GSDFile,
+    rename: &HashMap<String, String>,
+) -> Result<(), GsdError> {
+    dst.check_writable("write chunks to")?;
+    src.check_frame(src_frame)?;
+    for name in src.chunk_names(src_frame) {
+        let (data, gsd_type, (n, m)) = src.read_chunk_bytes(src_frame, &name)?;
+        let dst_name = rename.get(&name).unwrap_or(&name);
+        // the buffer holds exactly the n * m elements read from the source
+        unsafe { dst.write_raw_chunk(dst_name, gsd_type, n, m, data.as_ptr() as *const c_void)? }
+    }
+    Ok(())
+}
+
+/// Handle for reading one chunk from many frames, created with `GSDFile::typed_chunk`. The
+/// name is converted for the C API and the GSD type of `T` resolved once, when the handle is
+/// created, rather than on every read.
+pub struct TypedChunk<T> {
+    name: String,
+    c_name: CString,
+    type_: GSDType,
+    _data: PhantomData<T>,
+}
+
+impl<T: Clone + num_traits::Num> TypedChunk<T> {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Read the chunk from `frame` of `file`. Each frame may store the chunk with a different
+    /// type, so the stored type is still compared against `T`, but only as a single byte.
+    pub fn read(&self, file: &GSDFile, frame: usize) -> Result<Array2<T>, GsdError> {
+        file.check_readable()?;
+        file.check_frame(frame)?;
+        // a handle usually reads each frame once, so this looks the entry up with the
+        // converted name instead of going through the cache of `find_chunk`
+        let index_entry = unsafe {
+            libgsd::gsd_find_chunk(
+                &file.handle as *const libgsd::gsd_handle,
+                frame as u64,
+                self.c_name.as_ptr(),
+            )
+            .as_ref()
+        }
+        .ok_or_else(|| GsdError::ChunkNotFound {
+            frame,
+            name: self.name.clone(),
+        })?;
+        if index_entry.type_ != self.type_ as u8 {
+            return Err(GsdError::TypeMismatch {
+                expected: self.type_,
+                found: entry_type(index_entry, &file.name)?,
+            });
+        }
+
+        let mut data = Array2::<T>::zeros((index_entry.N as usize, index_entry.M as usize));
+        let out = data.as_slice_mut().expect("new arrays are contiguous");
+        file.read_entry_into(index_entry, out)?;
+        Ok(data)
+    }
+}
+
+/// Call `f` on every frame in `frames` using rayon's thread pool, and return the results in
+/// frame order.
+///
+/// GSD handles cannot be shared between threads, so each worker opens its own handle to `path`
+/// and passes it to `f` along with the frame index. Only reading is supported: the handles are
+/// opened with `Mode::Read`. Frames past the end of the file are a `FrameOutOfRange` error,
+/// while empty ranges return no results wherever they start.
+#[cfg(feature = "rayon")]
+pub fn read_frames_parallel<F, R>(
+    path: &Path,
+    frames: std::ops::Range<usize>,
+    f: F,
+) -> Result<Vec<R>, GsdError>
+where
+    F: Fn(&mut GSDFile, usize) -> R + Sync,
+    R: Send,
+{
+    use rayon::prelude::*;
+
+    let nframes = GSDFile::open(path, Mode::Read, None)?.nframes();
+    if !frames.is_empty() && frames.end > nframes {
+        return Err(GsdError::FrameOutOfRange {
+            frame: frames.end - 1,
+            nframes,
+        });
+    }
+
+    frames
+        .into_par_iter()
+        .map_init(
+            || GSDFile::open(path, Mode::Read, None),
+            |file, frame| match file {
+                Ok(file) => Ok(f(file, frame)),
+                Err(err) => Err(err.clone()),
+            },
+        )
+        .collect()
+}
+
+#[cfg(feature = "mmap")]
+impl GSDFile {
+    /// Open `path` read-only and map it into memory, so chunks can be viewed in place with
+    /// `MmapGsd::read_chunk_view` instead of being copied.
+    pub fn open_mmap<P: AsRef<Path>>(path: P) -> Result<MmapGsd, GsdError> {
+        let path = path.as_ref();
+        let file = GSDFile::open(path, Mode::Read, None)?;
+        let map = std::fs::File::open(path)
+            .and_then(|handle| unsafe { memmap2::Mmap::map(&handle) })
+            .map_err(|e| GsdError::IO(format!("{}: {}", e, path.display())))?;
+        Ok(MmapGsd { file, map })
+    }
+}
+
+/// A read-only GSD file mapped into memory. The index is read through a regular `GSDFile`,
+/// available with `file()`, while chunk data is viewed directly in the mapped pages.
+///
+/// As with any memory map, the file must not be modified or truncated by another process while
+/// it is mapped.
+#[cfg(feature = "mmap")]
+pub struct MmapGsd {
+    file: GSDFile,
+    map: memmap2::Mmap,
+}
+
+#[cfg(feature = "mmap")]
+impl MmapGsd {
+    pub fn file(&self) -> &GSDFile {
+        &self.file
+    }
+
+    /// View a chunk in place. The stored type must be `T`. Chunks whose data is not aligned
+    /// for `T` in the file cannot be viewed, so these are copied instead; the result is
+    /// borrowed whenever possible.
+    pub fn read_chunk_view<T: Clone + num_traits::Num>(
+        &self,
+        frame: usize,
+        name: &str,
+    ) -> Result<CowArray<'_, T, Ix2>, GsdError> {
+        self.file.check_frame(frame)?;
+        let index_entry =
+            self.file
+                .find_chunk(frame, name)
+                .ok_or_else(|| GsdError::ChunkNotFound {
+                    frame,
+                    name: name.to_owned(),
+                })?;
+        entry_type(index_entry, &self.file.name)?.check_match::<T>()?;
+
+        let shape = (index_entry.N as usize, index_entry.M as usize);
+        let out_of_bounds = || {
+            GsdError::FileCorrupt(format!(
+                "chunk {} in frame {} extends past the end of: {}",
+                name, frame, self.file.name
+            ))
+        };
+        let start = usize::try_from(index_entry.location).map_err(|_| out_of_bounds())?;
+        let end = shape
+            .0
+            .checked_mul(shape.1)
+            .and_then(|n| n.checked_mul(std::mem::size_of::<T>()))
+            .and_then(|n| start.checked_add(n))
+            .ok_or_else(out_of_bounds)?;
+        let bytes = self.map.get(start..end).ok_or_else(out_of_bounds)?;
+
+        // big-endian hosts need a byte swapped copy of the little-endian data
+        if cfg!(target_endian = "big") || bytes.as_ptr() as usize % std::mem::align_of::<T>() != 0 {
+            debug!(
+                "Copying unaligned or byte swapped chunk {} in frame {} of: {}",
+                name, frame, self.file.name
+            );
+            return Ok(self.file.read_chunk::<T>(frame, name)?.into());
+        }
+        // the type, bounds and alignment are checked above, and the map outlives the view
+        let view = unsafe { ArrayView2::from_shape_ptr(shape, bytes.as_ptr() as *const T) };
+        Ok(view.into())
+    }
+}
@@ -1,61 +1,893 @@
+use std::cell::{Ref, RefCell};
+use std::collections::HashMap;
+use std::fmt;
 use std::ops::Range;
-use ndarray::Slice;
+use std::path::Path;
+use ndarray::{Array2, ArrayD, ArrayView3, Axis, Slice};
 
-use crate::fl::GSDFile;
+use crate::fl::{GSDFile, Metadata, Mode};
+use crate::{GSDType, GsdError};
 
-#[derive(Default)]
+/// Read `configuration/box` from `frame`. HOOMD stores the box in single precision, but other
+/// writers may use doubles, so the stored type is checked and converted as needed.
+pub(crate) fn read_box(file: &GSDFile, frame: usize) -> Result<[f32; 6], GsdError> {
+    let name = "configuration/box";
+    let data: Vec<f32> = match file.chunk_info(frame, name)?.type_ {
+        GSDType::FLOAT => file.read_chunk_flat::<f32>(frame, name)?.to_vec(),
+        GSDType::DOUBLE => file
+            .read_chunk_flat::<f64>(frame, name)?
+            .iter()
+            .map(|&x| x as f32)
+            .collect(),
+        found => {
+            return Err(GsdError::TypeMismatch {
+                expected: GSDType::FLOAT,
+                found,
+            })
+        }
+    };
+    data.try_into().map_err(|_| {
+        GsdError::InvalidArgument(format!("{} must hold 6 values: {}", name, file.name()))
+    })
+}
+
+/// HOOMD schema versions this module can read.
+const SCHEMA_VERSIONS: Range<(u32, u32)> = (1, 0)..(2, 0);
+
+/// Box used by HOOMD when a file does not store `configuration/box`.
+const DEFAULT_BOX: [f32; 6] = [1.0, 1.0, 1.0, 0.0, 0.0, 0.0];
+
+/// Volume of a HOOMD box, or its area when `dimensions` is 2. The tilt factors do not change
+/// the volume of a triclinic box.
+fn box_volume(box_: &[f32; 6], dimensions: u8) -> f32 {
+    if dimensions == 2 {
+        box_[0] * box_[1]
+    } else {
+        box_[0] * box_[1] * box_[2]
+    }
+}
+
+/// A logged quantity read from a `log/` chunk, in the type it was stored with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogValue {
+    UINT8(ArrayD<u8>),
+    UINT16(ArrayD<u16>),
+    UINT32(ArrayD<u32>),
+    UINT64(ArrayD<u64>),
+    INT8(ArrayD<i8>),
+    INT16(ArrayD<i16>),
+    INT32(ArrayD<i32>),
+    INT64(ArrayD<i64>),
+    FLOAT(ArrayD<f32>),
+    DOUBLE(ArrayD<f64>),
+}
+
+/// Simulation step, dimensionality and box of a frame.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConfigurationData {
     step: u64,
     dimensions: u8,
     box_: [f32; 6],
 }
 
-#[derive(Default)]
-pub struct ParticleData<'a> {
-    n: u32,
-    position: Option<&'a [[f32; 3]]>,
-    orientation: Option<&'a [[f32; 4]]>,
-    typeid: Option<&'a [u32]>,
-    mass: Option<&'a [f32]>,
-    charge: Option<&'a [f32]>,
-    diameter: Option<&'a [f32]>,
-    body: Option<&'a [i32]>,
-    moment_inertia: Option<&'a [[f32; 3]]>,
-    velocity: Option<&'a [[f32; 3]]>,
-    angmom: Option<&'a [[f32; 4]]>,
-    image: Option<&'a [[f32; 3]]>,
-    types: Option<&'a [&'a str]>,
-}
-
-#[derive(Default)]
-pub struct BondData<'a, const M: usize> {
-    n: u32,
-    types: &'a [&'a str],
-    typeid: &'a [u32],
-    group: &'a [[u32; M]],
+impl Default for ConfigurationData {
+    fn default() -> Self {
+        Self {
+            step: 0,
+            dimensions: 3,
+            box_: DEFAULT_BOX,
+        }
+    }
+}
+
+impl ConfigurationData {
+    pub fn new(step: u64, dimensions: u8, box_: [f32; 6]) -> Self {
+        Self {
+            step,
+            dimensions,
+            box_,
+        }
+    }
+
+    pub fn step(&self) -> u64 {
+        self.step
+    }
+
+    pub fn dimensions(&self) -> u8 {
+        self.dimensions
+    }
+
+    /// Box lengths and tilt factors, `[lx, ly, lz, xy, xz, yz]`.
+    pub fn box_(&self) -> [f32; 6] {
+        self.box_
+    }
+
+    /// Upper triangular matrix whose columns are the box vectors
+    /// `a1 = (lx, 0, 0)`, `a2 = (xy ly, ly, 0)` and `a3 = (xz lz, yz lz, lz)`.
+    pub fn box_matrix(&self) -> [[f32; 3]; 3] {
+        let [lx, ly, lz, xy, xz, yz] = self.box_;
+        [[lx, xy * ly, xz * lz], [0.0, ly, yz * lz], [0.0, 0.0, lz]]
+    }
+
+    /// Volume of the box, or its area in 2D.
+    pub fn box_volume(&self) -> f32 {
+        box_volume(&self.box_, self.dimensions)
+    }
+}
+
+/// Per-particle data. Scalar quantities are stored as one value per particle, vector and
+/// quaternion quantities as `(N, 3)` and `(N, 4)` arrays. Fields the file does not provide are
+/// `None`.
+#[derive(Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParticleData {
+    pub n: u32,
+    #[cfg_attr(feature = "serde", serde(with = "nested_list::option"))]
+    pub position: Option<Array2<f32>>,
+    #[cfg_attr(feature = "serde", serde(with = "nested_list::option"))]
+    pub orientation: Option<Array2<f32>>,
+    pub typeid: Option<Vec<u32>>,
+    pub mass: Option<Vec<f32>>,
+    pub charge: Option<Vec<f32>>,
+    pub diameter: Option<Vec<f32>>,
+    pub body: Option<Vec<i32>>,
+    #[cfg_attr(feature = "serde", serde(with = "nested_list::option"))]
+    pub moment_inertia: Option<Array2<f32>>,
+    #[cfg_attr(feature = "serde", serde(with = "nested_list::option"))]
+    pub velocity: Option<Array2<f32>>,
+    #[cfg_attr(feature = "serde", serde(with = "nested_list::option"))]
+    pub angmom: Option<Array2<f32>>,
+    /// Periodic image flags, stored by HOOMD as `INT32`.
+    #[cfg_attr(feature = "serde", serde(with = "nested_list::option"))]
+    pub image: Option<Array2<i32>>,
+    /// Type names, indexed by `typeid`. An empty list, or no list in frame 0, is written as the
+    /// single type `"A"`, which is also what HOOMD assumes when a file stores no types.
+    pub types: Option<Vec<String>>,
+}
+
+#[derive(Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Groups of `M` particles: bonds and pairs (2), angles (3), dihedrals and impropers (4).
+/// `group` holds one row of `M` particle indices per group.
+pub struct BondData<const M: usize> {
+    pub n: u32,
+    pub types: Vec<String>,
+    pub typeid: Vec<u32>,
+    #[cfg_attr(feature = "serde", serde(with = "nested_list"))]
+    pub group: Array2<u32>,
+}
+
+#[derive(Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Distance constraints: `group` holds one row per constrained pair of particles and `value`
+/// their distances.
+pub struct ConstraintData {
+    pub n: u32,
+    pub value: Vec<f32>,
+    #[cfg_attr(feature = "serde", serde(with = "nested_list"))]
+    pub group: Array2<u32>,
+}
+
+#[derive(Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// One frame of a HOOMD trajectory. All data is owned, so snapshots outlive the trajectory
+/// they were read from.
+///
+/// With the `serde` feature, snapshots can be serialized, with arrays written as nested lists
+/// of rows. Formats without NaN or infinity need care: `serde_json`, for one, writes them as
+/// `null`, which then fails to deserialize as `f32`.
+pub struct OwnedSnapshot {
+    pub configuration: ConfigurationData,
+    pub particles: ParticleData,
+    pub bonds: BondData<2>,
+    pub angles: BondData<3>,
+    pub dihedrals: BondData<4>,
+    pub impropers: BondData<4>,
+    pub constraints: ConstraintData,
+    pub pairs: BondData<2>,
+}
+
+impl<const M: usize> BondData<M> {
+    /// Keep the groups whose particles all have a new index, renumbering them.
+    fn select(&self, new_index: &[Option<u32>]) -> BondData<M> {
+        let (kept, group) = select_groups(&self.group, new_index);
+        BondData {
+            n: kept.len() as u32,
+            types: self.types.clone(),
+            typeid: kept
+                .iter()
+                .filter_map(|&i| self.typeid.get(i).copied())
+                .collect(),
+            group,
+        }
+    }
+}
+
+/// Rows of `group` whose particles all have a new index, with the particles renumbered, along
+/// with the indices of the rows kept.
+fn select_groups(group: &Array2<u32>, new_index: &[Option<u32>]) -> (Vec<usize>, Array2<u32>) {
+    if group.is_empty() {
+        return (Vec::new(), group.clone());
+    }
+    let mut kept = Vec::new();
+    let mut data = Vec::new();
+    for (i, row) in group.outer_iter().enumerate() {
+        let renumbered: Option<Vec<u32>> = row
+            .iter()
+            .map(|&particle| new_index.get(particle as usize).copied().flatten())
+            .collect();
+        if let Some(renumbered) = renumbered {
+            kept.push(i);
+            data.extend(renumbered);
+        }
+    }
+    let group = Array2::from_shape_vec((kept.len(), group.ncols()), data)
+        .expect("each kept row has one entry per column");
+    (kept, group)
+}
+
+impl ConstraintData {
+    /// Constraints holding each pair of particles at the given distance.
+    pub fn new(constraints: &[([u32; 2], f32)]) -> Self {
+        let group = constraints.iter().flat_map(|(pair, _)| *pair).collect();
+        ConstraintData {
+            n: constraints.len() as u32,
+            value: constraints.iter().map(|&(_, value)| value).collect(),
+            group: Array2::from_shape_vec((constraints.len(), 2), group)
+                .expect("each constraint has two particles"),
+        }
+    }
+}
+
+impl ParticleData {
+    /// Name of the type of `particle`, or `None` when `typeid` or `types` is not set or either
+    /// index is out of range.
+    pub fn type_name(&self, particle: usize) -> Option<&str> {
+        let typeid = *self.typeid.as_ref()?.get(particle)?;
+        self.types
+            .as_ref()?
+            .get(typeid as usize)
+            .map(String::as_str)
+    }
+
+    /// Number of particles of each type, by name. Particles whose type cannot be resolved, see
+    /// `type_name`, are not counted.
+    pub fn type_counts(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        let n = self.typeid.as_ref().map_or(0, Vec::len);
+        for name in (0..n).filter_map(|particle| self.type_name(particle)) {
+            *counts.entry(name.to_owned()).or_insert(0) += 1;
+        }
+        counts
+    }
 }
 
-#[derive(Default)]
-pub struct ConstraintData<'a> {
+// Snapshots hold arrays with one row per particle, so their `Debug` output summarizes counts
+// and which optional fields are present instead of printing every element.
+
+impl fmt::Debug for ParticleData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = [
+            ("position", self.position.is_some()),
+            ("orientation", self.orientation.is_some()),
+            ("typeid", self.typeid.is_some()),
+            ("mass", self.mass.is_some()),
+            ("charge", self.charge.is_some()),
+            ("diameter", self.diameter.is_some()),
+            ("body", self.body.is_some()),
+            ("moment_inertia", self.moment_inertia.is_some()),
+            ("velocity", self.velocity.is_some()),
+            ("angmom", self.angmom.is_some()),
+            ("image", self.image.is_some()),
+        ];
+        let present: Vec<&str> = fields
+            .iter()
+            .filter(|(_, is_some)| *is_some)
+            .map(|(name, _)| *name)
+            .collect();
+        f.debug_struct("ParticleData")
+            .field("n", &self.n)
+            .field("types", &self.types)
+            .field("present", &present)
+            .finish()
+    }
+}
+
+impl<const M: usize> fmt::Debug for BondData<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(&format!("BondData<{}>", M))
+            .field("n", &self.n)
+            .field("types", &self.types)
+            .finish()
+    }
+}
+
+impl fmt::Debug for ConstraintData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConstraintData")
+            .field("n", &self.n)
+            .finish()
+    }
+}
+
+impl fmt::Debug for OwnedSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedSnapshot")
+            .field("configuration", &self.configuration)
+            .field("particles", &self.particles)
+            .field("bonds", &self.bonds)
+            .field("angles", &self.angles)
+            .field("dihedrals", &self.dihedrals)
+            .field("impropers", &self.impropers)
+            .field("constraints", &self.constraints)
+            .field("pairs", &self.pairs)
+            .finish()
+    }
+}
+
+impl OwnedSnapshot {
+    /// Check that every per-particle array that is set has `particles.n` rows, and that the
+    /// typeids and groups of bonds, angles, dihedrals, impropers, constraints and pairs match
+    /// the `n` of their section. The box always holds 6 values, as it is a fixed-size array.
+    /// The error names the first offending chunk.
+    pub fn validate(&self) -> Result<(), GsdError> {
+        let p = &self.particles;
+        let n = p.n;
+        check_optional_rows("particles/position", &p.position, Array2::nrows, n)?;
+        check_optional_rows("particles/orientation", &p.orientation, Array2::nrows, n)?;
+        check_optional_rows("particles/typeid", &p.typeid, Vec::len, n)?;
+        check_optional_rows("particles/mass", &p.mass, Vec::len, n)?;
+        check_optional_rows("particles/charge", &p.charge, Vec::len, n)?;
+        check_optional_rows("particles/diameter", &p.diameter, Vec::len, n)?;
+        check_optional_rows("particles/body", &p.body, Vec::len, n)?;
+        check_optional_rows(
+            "particles/moment_inertia",
+            &p.moment_inertia,
+            Array2::nrows,
+            n,
+        )?;
+        check_optional_rows("particles/velocity", &p.velocity, Array2::nrows, n)?;
+        check_optional_rows("particles/angmom", &p.angmom, Array2::nrows, n)?;
+        check_optional_rows("particles/image", &p.image, Array2::nrows, n)?;
+
+        self.bonds.validate("bonds")?;
+        self.angles.validate("angles")?;
+        self.dihedrals.validate("dihedrals")?;
+        self.impropers.validate("impropers")?;
+        let constraints = &self.constraints;
+        if !constraints.value.is_empty() {
+            check_rows("constraints/value", constraints.value.len(), constraints.n)?;
+        }
+        if !constraints.group.is_empty() {
+            check_rows(
+                "constraints/group",
+                constraints.group.nrows(),
+                constraints.n,
+            )?;
+            if constraints.group.ncols() != 2 {
+                return Err(GsdError::InvalidArgument(format!(
+                    "constraints/group must have 2 columns, not {}",
+                    constraints.group.ncols()
+                )));
+            }
+        }
+        self.pairs.validate("pairs")
+    }
+
+    /// Positions wrapped into the periodic box, with every coordinate inside `[-L/2, L/2)`
+    /// along the box vectors of the triclinic box. Image flags only add whole box vectors to
+    /// a position, so the result is the same whether or not they are set. In 2D the `z`
+    /// coordinates are left as they are. Missing positions default to the origin, as in HOOMD.
+    pub fn wrap_positions(&self) -> Array2<f32> {
+        let [lx, ly, lz, xy, xz, yz] = self.configuration.box_;
+        let three_d = self.configuration.dimensions != 2;
+        let mut wrapped = match &self.particles.position {
+            Some(position) => position.clone(),
+            None => Array2::zeros((self.particles.n as usize, 3)),
+        };
+
+        let wrap = |s: f32| s - (s + 0.5).floor();
+        for mut r in wrapped.outer_iter_mut() {
+            // fractional coordinates along the box vectors
+            let s3 = if three_d { r[2] / lz } else { 0.0 };
+            let s2 = (r[1] - yz * lz * s3) / ly;
+            let s1 = (r[0] - xy * ly * s2 - xz * lz * s3) / lx;
+
+            let (s1, s2) = (wrap(s1), wrap(s2));
+            let s3 = if three_d { wrap(s3) } else { 0.0 };
+            r[0] = s1 * lx + s2 * xy * ly + s3 * xz * lz;
+            r[1] = s2 * ly + s3 * yz * lz;
+            if three_d {
+                r[2] = s3 * lz;
+            }
+        }
+        wrapped
+    }
+
+    /// Snapshot of only the particles in `indices`, in that order. Particles are renumbered
+    /// contiguously, so particle `indices[i]` becomes particle `i`, and bonds, angles, and the
+    /// other groups that reference an excluded particle are dropped. `body` values are copied
+    /// unchanged. Panics when an index is out of range; indices should be distinct.
+    pub fn select(&self, indices: &[usize]) -> OwnedSnapshot {
+        let mut new_index = vec![None; self.particles.n as usize];
+        for (new, &old) in indices.iter().enumerate().rev() {
+            new_index[old] = Some(new as u32);
+        }
+
+        let p = &self.particles;
+        let rows = |a: &Option<Array2<f32>>| a.as_ref().map(|a| a.select(Axis(0), indices));
+        let values = |v: &Option<Vec<f32>>| {
+            v.as_ref()
+                .map(|v| indices.iter().map(|&i| v[i]).collect::<Vec<_>>())
+        };
+        let particles = ParticleData {
+            n: indices.len() as u32,
+            position: rows(&p.position),
+            orientation: rows(&p.orientation),
+            typeid: p
+                .typeid
+                .as_ref()
+                .map(|v| indices.iter().map(|&i| v[i]).collect()),
+            mass: values(&p.mass),
+            charge: values(&p.charge),
+            diameter: values(&p.diameter),
+            body: p
+                .body
+                .as_ref()
+                .map(|v| indices.iter().map(|&i| v[i]).collect()),
+            moment_inertia: rows(&p.moment_inertia),
+            velocity: rows(&p.velocity),
+            angmom: rows(&p.angmom),
+            image: p.image.as_ref().map(|a| a.select(Axis(0), indices)),
+            types: p.types.clone(),
+        };
+
+        let (kept, group) = select_groups(&self.constraints.group, &new_index);
+        let constraints = ConstraintData {
+            n: kept.len() as u32,
+            value: kept
+                .iter()
+                .filter_map(|&i| self.constraints.value.get(i).copied())
+                .collect(),
+            group,
+        };
+
+        OwnedSnapshot {
+            configuration: self.configuration.clone(),
+            particles,
+            bonds: self.bonds.select(&new_index),
+            angles: self.angles.select(&new_index),
+            dihedrals: self.dihedrals.select(&new_index),
+            impropers: self.impropers.select(&new_index),
+            constraints,
+            pairs: self.pairs.select(&new_index),
+        }
+    }
+
+    /// Compare with `other`, allowing floating point fields (box, positions, velocities and
+    /// other per-particle quantities, constraint values) to differ by up to `tol`. Integer
+    /// fields, type names and bond groups must match exactly, and an optional field set on
+    /// only one side is a difference. NaN never compares equal.
+    pub fn approx_eq(&self, other: &OwnedSnapshot, tol: f32) -> bool {
+        let (a, b) = (&self.particles, &other.particles);
+        let (c, d) = (&self.configuration, &other.configuration);
+        c.step == d.step
+            && c.dimensions == d.dimensions
+            && close(&c.box_, &d.box_, tol)
+            && a.n == b.n
+            && rows_close(&a.position, &b.position, tol)
+            && rows_close(&a.orientation, &b.orientation, tol)
+            && a.typeid == b.typeid
+            && values_close(&a.mass, &b.mass, tol)
+            && values_close(&a.charge, &b.charge, tol)
+            && values_close(&a.diameter, &b.diameter, tol)
+            && a.body == b.body
+            && rows_close(&a.moment_inertia, &b.moment_inertia, tol)
+            && rows_close(&a.velocity, &b.velocity, tol)
+            && rows_close(&a.angmom, &b.angmom, tol)
+            && a.image == b.image
+            && a.types == b.types
+            && self.bonds == other.bonds
+            && self.angles == other.angles
+            && self.dihedrals == other.dihedrals
+            && self.impropers == other.impropers
+            && self.constraints.n == other.constraints.n
+            && close(&self.constraints.value, &other.constraints.value, tol)
+            && self.constraints.group == other.constraints.group
+            && self.pairs == other.pairs
+    }
+
+    /// Body-frame angular velocity of every particle, derived from `angmom`, `moment_inertia`
+    /// and `orientation` (the identity when absent). HOOMD stores the angular momentum as the
+    /// quaternion `p = 2 q (0, L)`, where `L` is the angular momentum in the body frame, so
+    /// `L = vec(q* p) / 2` and each component of the angular velocity is `L_i / I_i`. Components
+    /// with a zero moment of inertia are zero, as in HOOMD's integrators. Returns `None` when
+    /// `angmom` or `moment_inertia` is not set.
+    pub fn angular_velocities(&self) -> Option<Array2<f32>> {
+        let angmom = self.particles.angmom.as_ref()?;
+        let moment_inertia = self.particles.moment_inertia.as_ref()?;
+
+        let mut omega = Array2::<f32>::zeros((angmom.nrows(), 3));
+        for (i, mut omega_i) in omega.outer_iter_mut().enumerate() {
+            let q = self
+                .particles
+                .orientation
+                .as_ref()
+                .map_or([1.0, 0.0, 0.0, 0.0], |orientation| quat_at(orientation, i));
+            let l = quat_mul(quat_conj(q), quat_at(angmom, i));
+            for k in 0..3 {
+                if moment_inertia[[i, k]] != 0.0 {
+                    omega_i[k] = 0.5 * l[k + 1] / moment_inertia[[i, k]];
+                }
+            }
+        }
+        Some(omega)
+    }
+}
+
+/// Row `i` of an `(N, 4)` array of quaternions.
+fn quat_at(quaternions: &Array2<f32>, i: usize) -> [f32; 4] {
+    [
+        quaternions[[i, 0]],
+        quaternions[[i, 1]],
+        quaternions[[i, 2]],
+        quaternions[[i, 3]],
+    ]
+}
+
+/// Product of two quaternions stored as `[s, x, y, z]`.
+fn quat_mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    [
+        a[0] * b[0] - a[1] * b[1] - a[2] * b[2] - a[3] * b[3],
+        a[0] * b[1] + a[1] * b[0] + a[2] * b[3] - a[3] * b[2],
+        a[0] * b[2] - a[1] * b[3] + a[2] * b[0] + a[3] * b[1],
+        a[0] * b[3] + a[1] * b[2] - a[2] * b[1] + a[3] * b[0],
+    ]
+}
+
+fn quat_conj(q: [f32; 4]) -> [f32; 4] {
+    [q[0], -q[1], -q[2], -q[3]]
+}
+
+/// Whether `a` and `b` have the same length and differ by at most `tol` element by element.
+fn close(a: &[f32], b: &[f32], tol: f32) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| (x - y).abs() <= tol)
+}
+
+fn values_close(a: &Option<Vec<f32>>, b: &Option<Vec<f32>>, tol: f32) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => close(a, b, tol),
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+fn rows_close(a: &Option<Array2<f32>>, b: &Option<Array2<f32>>, tol: f32) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            a.dim() == b.dim() && a.iter().zip(b).all(|(x, y)| (x - y).abs() <= tol)
+        }
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+/// Parse a Python style `start:stop[:step]` slice over `len` items.
+fn parse_slice(spec: &str, len: usize) -> Result<(Range<usize>, usize), GsdError> {
+    let invalid =
+        |reason: &str| GsdError::InvalidArgument(format!("invalid slice {:?}: {}", spec, reason));
+    let parts: Vec<&str> = spec.split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        return Err(invalid("expected start:stop or start:stop:step"));
+    }
+    let index = |part: &str, default: usize| -> Result<usize, GsdError> {
+        let part = part.trim();
+        if part.is_empty() {
+            return Ok(default);
+        }
+        let value: i64 = part
+            .parse()
+            .map_err(|_| invalid("indices must be integers"))?;
+        Ok(if value < 0 {
+            len.saturating_sub(value.unsigned_abs() as usize)
+        } else {
+            (value as usize).min(len)
+        })
+    };
+    let start = index(parts[0], 0)?;
+    let stop = index(parts[1], len)?;
+    let step = match parts.get(2).map(|part| part.trim()) {
+        None | Some("") => 1,
+        Some(part) => match part.parse::<usize>() {
+            Ok(step) if step > 0 => step,
+            _ => return Err(invalid("step must be a positive integer")),
+        },
+    };
+    Ok((start..stop.max(start), step))
+}
+
+/// Whether `value` has to be written, given the value `initial` stored in frame 0. Everything is
+/// written to frame 0 itself, which has no initial value.
+fn changed<T: PartialEq>(value: &T, initial: Option<&T>) -> bool {
+    initial != Some(value)
+}
+
+fn check_rows(name: &str, rows: usize, n: u32) -> Result<(), GsdError> {
+    if rows != n as usize {
+        return Err(GsdError::InvalidArgument(format!(
+            "{} has {} rows, but N is {}",
+            name, rows, n
+        )));
+    }
+    Ok(())
+}
+
+fn check_optional_rows<T>(
+    name: &str,
+    value: &Option<T>,
+    rows: impl Fn(&T) -> usize,
     n: u32,
-    value: &'a [f32],
-    group: &'a [[f32; 2]],
+) -> Result<(), GsdError> {
+    match value {
+        Some(value) => check_rows(name, rows(value), n),
+        None => Ok(()),
+    }
+}
+
+impl<const M: usize> BondData<M> {
+    fn validate(&self, prefix: &str) -> Result<(), GsdError> {
+        if !self.typeid.is_empty() {
+            check_rows(&format!("{}/typeid", prefix), self.typeid.len(), self.n)?;
+        }
+        if !self.group.is_empty() {
+            let name = format!("{}/group", prefix);
+            check_rows(&name, self.group.nrows(), self.n)?;
+            if self.group.ncols() != M {
+                return Err(GsdError::InvalidArgument(format!(
+                    "{} must have {} columns, not {}",
+                    name,
+                    M,
+                    self.group.ncols()
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
-#[derive(Default)]
-pub struct Snapshot<'a> {
-    configuration: ConfigurationData,
-    particles: ParticleData<'a>,
-    bonds: BondData<'a, 2>,
-    angles: BondData<'a, 3>,
-    dihedrals: BondData<'a, 4>,
-    impropers: BondData<'a, 4>,
-    constraints: ConstraintData<'a>,
-    pairs: BondData<'a, 2>,
+/// Write an optional `(N, M)` array when it is set and differs from frame 0.
+fn write_rows<T: Clone + PartialEq + num_traits::Num>(
+    file: &mut GSDFile,
+    name: &str,
+    value: &Option<Array2<T>>,
+    initial: Option<&Option<Array2<T>>>,
+) -> Result<(), GsdError> {
+    match value {
+        Some(data) if changed(value, initial) => file.write_chunk(name, data),
+        _ => Ok(()),
+    }
+}
+
+/// Write an optional per-particle scalar when it is set and differs from frame 0.
+fn write_values<T: Clone + PartialEq + num_traits::Num>(
+    file: &mut GSDFile,
+    name: &str,
+    value: &Option<Vec<T>>,
+    initial: Option<&Option<Vec<T>>>,
+) -> Result<(), GsdError> {
+    match value {
+        Some(data) if changed(value, initial) => file.write_chunk(name, data),
+        _ => Ok(()),
+    }
+}
+
+fn write_particles(
+    file: &mut GSDFile,
+    particles: &ParticleData,
+    initial: Option<&ParticleData>,
+) -> Result<(), GsdError> {
+    if changed(&particles.n, initial.map(|p| &p.n)) {
+        file.write_chunk("particles/N", &vec![particles.n])?;
+    }
+    write_rows(
+        file,
+        "particles/position",
+        &particles.position,
+        initial.map(|p| &p.position),
+    )?;
+    write_rows(
+        file,
+        "particles/orientation",
+        &particles.orientation,
+        initial.map(|p| &p.orientation),
+    )?;
+    write_values(
+        file,
+        "particles/typeid",
+        &particles.typeid,
+        initial.map(|p| &p.typeid),
+    )?;
+    // HOOMD needs at least one type name and uses "A" when it has no others, so frame 0
+    // always stores the table while later frames only store it when it changes
+    let types = particles.types.as_deref().unwrap_or_default();
+    if initial.is_none()
+        || (particles.types.is_some() && changed(&particles.types, initial.map(|p| &p.types)))
+    {
+        let types: Vec<&str> = if types.is_empty() {
+            vec!["A"]
+        } else {
+            types.iter().map(String::as_str).collect()
+        };
+        file.write_string_chunk("particles/types", &types)?;
+    }
+    write_values(
+        file,
+        "particles/mass",
+        &particles.mass,
+        initial.map(|p| &p.mass),
+    )?;
+    write_values(
+        file,
+        "particles/charge",
+        &particles.charge,
+        initial.map(|p| &p.charge),
+    )?;
+    write_values(
+        file,
+        "particles/diameter",
+        &particles.diameter,
+        initial.map(|p| &p.diameter),
+    )?;
+    write_values(
+        file,
+        "particles/body",
+        &particles.body,
+        initial.map(|p| &p.body),
+    )?;
+    write_rows(
+        file,
+        "particles/moment_inertia",
+        &particles.moment_inertia,
+        initial.map(|p| &p.moment_inertia),
+    )?;
+    write_rows(
+        file,
+        "particles/velocity",
+        &particles.velocity,
+        initial.map(|p| &p.velocity),
+    )?;
+    write_rows(
+        file,
+        "particles/angmom",
+        &particles.angmom,
+        initial.map(|p| &p.angmom),
+    )?;
+    write_rows(
+        file,
+        "particles/image",
+        &particles.image,
+        initial.map(|p| &p.image),
+    )
+}
+
+/// Write the `{prefix}/*` chunks of a group of `M` particles, skipping data that is empty or
+/// stored in frame 0.
+fn write_bonds<const M: usize>(
+    file: &mut GSDFile,
+    prefix: &str,
+    bonds: &BondData<M>,
+    initial: Option<&BondData<M>>,
+) -> Result<(), GsdError> {
+    if changed(&bonds.n, initial.map(|b| &b.n)) {
+        file.write_chunk(&format!("{}/N", prefix), &vec![bonds.n])?;
+    }
+    if !bonds.types.is_empty() && changed(&bonds.types, initial.map(|b| &b.types)) {
+        let types: Vec<&str> = bonds.types.iter().map(String::as_str).collect();
+        file.write_string_chunk(&format!("{}/types", prefix), &types)?;
+    }
+    if !bonds.typeid.is_empty() && changed(&bonds.typeid, initial.map(|b| &b.typeid)) {
+        file.write_chunk(&format!("{}/typeid", prefix), &bonds.typeid)?;
+    }
+    if !bonds.group.is_empty() && changed(&bonds.group, initial.map(|b| &b.group)) {
+        file.write_chunk(&format!("{}/group", prefix), &bonds.group)?;
+    }
+    Ok(())
+}
+
+fn write_constraints(
+    file: &mut GSDFile,
+    constraints: &ConstraintData,
+    initial: Option<&ConstraintData>,
+) -> Result<(), GsdError> {
+    if changed(&constraints.n, initial.map(|c| &c.n)) {
+        file.write_chunk("constraints/N", &vec![constraints.n])?;
+    }
+    if !constraints.value.is_empty() && changed(&constraints.value, initial.map(|c| &c.value)) {
+        file.write_chunk("constraints/value", &constraints.value)?;
+    }
+    if !constraints.group.is_empty() && changed(&constraints.group, initial.map(|c| &c.group)) {
+        file.write_chunk("constraints/group", &constraints.group)?;
+    }
+    Ok(())
+}
+
+/// Write HOOMD's default value of chunk `name` for `n` particles. Log and custom chunks have
+/// no default and are left unwritten.
+fn write_hoomd_default(file: &mut GSDFile, name: &str, n: usize) -> Result<(), GsdError> {
+    match name {
+        "configuration/step" => file.write_chunk(name, &vec![0u64])?,
+        "configuration/dimensions" => file.write_chunk(name, &vec![3u8])?,
+        "configuration/box" => file.write_chunk(name, &DEFAULT_BOX.to_vec())?,
+        "particles/types" => file.write_string_chunk(name, &["A"])?,
+        "particles/typeid" => file.write_chunk(name, &vec![0u32; n])?,
+        "particles/mass" | "particles/diameter" => file.write_chunk(name, &vec![1.0f32; n])?,
+        "particles/charge" => file.write_chunk(name, &vec![0.0f32; n])?,
+        "particles/body" => file.write_chunk(name, &vec![-1i32; n])?,
+        "particles/position" | "particles/velocity" | "particles/moment_inertia" => {
+            file.write_chunk(name, &Array2::<f32>::zeros((n, 3)))?
+        }
+        "particles/orientation" => file.write_chunk(
+            name,
+            &Array2::from_shape_fn((n, 4), |(_, j)| if j == 0 { 1.0f32 } else { 0.0 }),
+        )?,
+        "particles/angmom" => file.write_chunk(name, &Array2::<f32>::zeros((n, 4)))?,
+        "particles/image" => file.write_chunk(name, &Array2::<i32>::zeros((n, 3)))?,
+        _ => {
+            let columns = |prefix: &str| match prefix {
+                "bonds" | "pairs" | "constraints" => Some(2),
+                "angles" => Some(3),
+                "dihedrals" | "impropers" => Some(4),
+                _ => None,
+            };
+            // groups default to none at all
+            match name.split_once('/') {
+                Some((prefix, "N")) if columns(prefix).is_some() => {
+                    file.write_chunk(name, &vec![0u32])?
+                }
+                Some((prefix, "types")) if columns(prefix).is_some() => {
+                    file.write_string_chunk(name, &[])?
+                }
+                Some((prefix, "typeid")) if columns(prefix).is_some() => {
+                    file.write_chunk(name, &Vec::<u32>::new())?
+                }
+                Some(("constraints", "value")) => file.write_chunk(name, &Vec::<f32>::new())?,
+                Some((prefix, "group")) => {
+                    if let Some(m) = columns(prefix) {
+                        file.write_chunk(name, &Array2::<u32>::zeros((0, m)))?
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write a log quantity of up to 2 dimensions as an `(N, M)` chunk.
+fn write_log_array<T: Clone + num_traits::Num>(
+    file: &mut GSDFile,
+    name: &str,
+    data: ArrayD<T>,
+) -> Result<(), GsdError> {
+    let shape = match *data.shape() {
+        [] => (1, 1),
+        [n] => (n, 1),
+        [n, m] => (n, m),
+        _ => {
+            return Err(GsdError::InvalidArgument(format!(
+                "GSD can only write 1 or 2 dimensional arrays: {}",
+                name
+            )))
+        }
+    };
+    let data = data
+        .as_standard_layout()
+        .into_shape(shape)
+        .map_err(|e| GsdError::InvalidArgument(format!("{}: {}", name, e)))?;
+    file.write_chunk(name, &data)
 }
 
 pub struct HOOMDTrajectoryIterator<'a> {
-    trajectory: &'a HOOMDTrajectory<'a>,
+    trajectory: &'a HOOMDTrajectory,
     slice: (Range<usize>, usize),
 }
 
@@ -68,67 +900,159 @@ impl<'a> HOOMDTrajectoryIterator<'a> {
         }
     }
 
-    fn len(&self) -> usize {
-        self.slice.0.len() / self.slice.1
+    /// Number of indices left, counting the partial stride at the end of the range.
+    fn remaining(&self) -> usize {
+        let (range, stride) = &self.slice;
+        if range.is_empty() {
+            0
+        } else {
+            (range.len() - 1) / stride + 1
+        }
+    }
+
+    fn next_index(&mut self) -> Option<usize> {
+        if self.slice.0.start >= self.slice.0.end {
+            return None;
+        }
+
+        let idx = self.slice.0.start;
+        self.slice.0.start = idx.saturating_add(self.slice.1);
+        Some(idx)
+    }
+
+    /// Take the last remaining index, which is the largest `start + k * stride` below `end`.
+    /// Moving `end` onto it keeps forward iteration from yielding it again.
+    fn next_back_index(&mut self) -> Option<usize> {
+        if self.slice.0.start >= self.slice.0.end {
+            return None;
+        }
+
+        let (range, stride) = &self.slice;
+        let idx = range.start + (range.len() - 1) / stride * stride;
+        self.slice.0.end = idx;
+        Some(idx)
     }
 }
 
 impl<'a> Iterator for HOOMDTrajectoryIterator<'a> {
-    type Item = Snapshot<'a>;
+    type Item = OwnedSnapshot;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.slice.0.start >= self.slice.0.end {
-            return None;
-        }
+        let idx = self.next_index()?;
+        Some(self.trajectory.index(idx))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for HOOMDTrajectoryIterator<'a> {}
+
+impl<'a> DoubleEndedIterator for HOOMDTrajectoryIterator<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let idx = self.next_back_index()?;
+        Some(self.trajectory.index(idx))
+    }
+}
+
+/// Iterator over frames that yields read errors instead of panicking on them. Iteration stops
+/// after the first error.
+pub struct HOOMDTrajectoryTryIterator<'a> {
+    inner: HOOMDTrajectoryIterator<'a>,
+}
 
-        let snapshot = self.trajectory.index(self.slice.0.start);
-        self.slice.0.start += self.slice.1;
+impl<'a> Iterator for HOOMDTrajectoryTryIterator<'a> {
+    type Item = Result<OwnedSnapshot, GsdError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.inner.next_index()?;
+        let snapshot = self.inner.trajectory._read_frame(idx);
+        if snapshot.is_err() {
+            self.inner.slice.0.start = self.inner.slice.0.end;
+        }
         Some(snapshot)
     }
 }
 
 pub struct HOOMDTrajectoryView<'a> {
-    trajectory: &'a HOOMDTrajectory<'a>,
+    trajectory: &'a HOOMDTrajectory,
     slice: (Range<usize>, usize),
 }
 
 impl<'a> HOOMDTrajectoryView<'a> {
-    fn index(&'a self, idx: usize) -> Snapshot<'a> {
-        let idx = self.slice.0.start + idx * self.slice.1;
-        if idx >= self.slice.0.end {
+    /// Read the frame at position `idx` of this view. Panics when `idx` is out of range or the
+    /// frame cannot be read.
+    pub fn index(&self, idx: usize) -> OwnedSnapshot {
+        if idx >= self.len() {
             panic!("index out of bounds");
         }
-        let snap = self.trajectory._read_frame(idx);
-        snap
+        self.at(idx).unwrap()
     }
 
-    fn view(&'a self, mut slice: (Range<usize>, usize)) -> HOOMDTrajectoryView<'a> {
-        let items = self.slice.0.len() / self.slice.1;
-        if slice.0.end > items {
-            slice.0.end = items;
+    /// Like `index`, but returns an error instead of panicking. Out of range positions are
+    /// reported as `GsdError::FrameOutOfRange` against the length of the view.
+    pub fn at(&self, idx: usize) -> Result<OwnedSnapshot, GsdError> {
+        if idx >= self.len() {
+            return Err(GsdError::FrameOutOfRange {
+                frame: idx,
+                nframes: self.len(),
+            });
         }
-        let slice = (
-            slice.0.start * self.slice.1 + self.slice.0.start
-                ..slice.0.end * self.slice.1 + self.slice.0.start,
-            slice.1 * self.slice.1,
-        );
+        self.trajectory
+            ._read_frame(self.slice.0.start + idx * self.slice.1)
+    }
+
+    /// View of the frames of this view in `slice.0`, taking every `slice.1`-th one. Indices
+    /// are positions within this view, and ranges past its end are clamped. Panics when the
+    /// step `slice.1` is 0.
+    pub fn view(&self, slice: (Range<usize>, usize)) -> HOOMDTrajectoryView<'a> {
+        assert!(slice.1 > 0, "view step must be at least 1");
+        let (range, stride) = &self.slice;
+        let items = self.len();
+        let end = slice.0.end.min(items);
+        let start = slice.0.start.min(end);
+        // position i of this view is frame range.start + i * stride
+        let frame = |i: usize| {
+            range
+                .start
+                .saturating_add(i.saturating_mul(*stride))
+                .min(range.end)
+        };
         HOOMDTrajectoryView {
             trajectory: self.trajectory,
-            slice,
+            slice: (frame(start)..frame(end), slice.1.saturating_mul(*stride)),
         }
     }
 
-    fn iter(&'a self) -> HOOMDTrajectoryIterator<'a> {
+    /// Iterate over the frames of this view, panicking on frames that cannot be read.
+    pub fn iter(&self) -> HOOMDTrajectoryIterator<'a> {
         self.into_iter()
     }
 
-    fn len(&self) -> usize {
-        self.slice.0.len() / self.slice.1
+    /// Like `iter`, but yields read errors instead of panicking on them.
+    pub fn try_iter(&self) -> HOOMDTrajectoryTryIterator<'a> {
+        HOOMDTrajectoryTryIterator { inner: self.iter() }
+    }
+
+    /// Number of frames in this view.
+    pub fn len(&self) -> usize {
+        let (range, stride) = &self.slice;
+        if range.is_empty() {
+            0
+        } else {
+            (range.len() - 1) / stride + 1
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 }
 
-impl<'a> IntoIterator for &'a HOOMDTrajectoryView<'a> {
-    type Item = Snapshot<'a>;
+impl<'a> IntoIterator for &HOOMDTrajectoryView<'a> {
+    type Item = OwnedSnapshot;
     type IntoIter = HOOMDTrajectoryIterator<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -140,82 +1064,824 @@ impl<'a> IntoIterator for &'a HOOMDTrajectoryView<'a> {
     }
 }
 
-pub struct HOOMDTrajectory<'a> {
+/// Builder for iterating over a subset of frames, created with `HOOMDTrajectory::frames`.
+/// `HOOMDTrajectory::frames().range(10..100).step_by(5)` visits the same frames as
+/// `view((10..100, 5))`.
+#[derive(Clone)]
+pub struct FrameIter<'a> {
+    trajectory: &'a HOOMDTrajectory,
+    range: Range<usize>,
+    stride: usize,
+}
+
+impl<'a> FrameIter<'a> {
+    /// Restrict iteration to the frames in `range`, clamped to the trajectory.
+    pub fn range(mut self, range: Range<usize>) -> Self {
+        let end = range.end.min(self.trajectory.len());
+        self.range = range.start.min(end)..end;
+        self
+    }
+
+    /// Take every `step`-th frame. Like `Iterator::step_by`, repeated calls multiply the
+    /// steps, and a step of 0 panics.
+    pub fn step_by(mut self, step: usize) -> Self {
+        assert!(step > 0);
+        self.stride *= step;
+        self
+    }
+
+    pub fn iter(&self) -> HOOMDTrajectoryIterator<'a> {
+        HOOMDTrajectoryIterator::new(self.trajectory, self.range.clone(), self.stride)
+    }
+
+    /// Like `iter`, but yields read errors instead of panicking on them.
+    pub fn try_iter(&self) -> HOOMDTrajectoryTryIterator<'a> {
+        HOOMDTrajectoryTryIterator { inner: self.iter() }
+    }
+}
+
+impl<'a> IntoIterator for FrameIter<'a> {
+    type Item = OwnedSnapshot;
+    type IntoIter = HOOMDTrajectoryIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &FrameIter<'a> {
+    type Item = OwnedSnapshot;
+    type IntoIter = HOOMDTrajectoryIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct HOOMDTrajectory {
     file: GSDFile,
-    initial_frame: Option<Snapshot<'a>>,
-    _phantom: std::marker::PhantomData<&'a ()>,
+    initial_frame: RefCell<Option<OwnedSnapshot>>,
 }
 
-impl<'a> HOOMDTrajectory<'a> {
+impl HOOMDTrajectory {
     pub fn new(file: GSDFile) -> Self {
         Self {
             file,
-            initial_frame: None,
-            _phantom: std::marker::PhantomData,
+            initial_frame: RefCell::new(None),
         }
     }
 
-    pub fn try_new(file: GSDFile) -> Self {
-        if file.mode() == "ab" {
-            panic!("Append not yet supported!");
-        }
+    /// Wrap `file` after checking that it holds a HOOMD trajectory with a supported schema
+    /// version.
+    pub fn try_new(file: GSDFile) -> Result<Self, GsdError> {
         if file.schema() != "hoomd" {
-            panic!("Not a HOOMD schema!");
+            return Err(GsdError::InvalidArgument(format!(
+                "schema {} is not hoomd: {}",
+                file.schema(),
+                file.name()
+            )));
         }
 
         let version = file.schema_version();
-        if version < (2, 0) && version >= (1, 0) {
-            panic!("Incompatible HOOMD schema version!")
+        if !SCHEMA_VERSIONS.contains(&version) {
+            return Err(GsdError::InvalidFileVersion(format!(
+                "hoomd schema version {}.{} is not supported: {}",
+                version.0,
+                version.1,
+                file.name()
+            )));
+        }
+
+        Ok(Self::new(file))
+    }
+
+    /// The underlying file, e.g. to read chunks outside the HOOMD schema.
+    pub fn file(&self) -> &GSDFile {
+        &self.file
+    }
+
+    /// Mutable access to the underlying file, e.g. to write custom chunks into the current
+    /// frame. The cached frame 0 is dropped, as the file may be changed through it.
+    pub fn file_mut(&mut self) -> &mut GSDFile {
+        self.initial_frame.get_mut().take();
+        &mut self.file
+    }
+
+    /// Unwrap the underlying file.
+    pub fn into_inner(self) -> GSDFile {
+        self.file
+    }
+
+    fn _read_frame(&self, idx: usize) -> Result<OwnedSnapshot, GsdError> {
+        if idx >= self.len() {
+            return Err(GsdError::FrameOutOfRange {
+                frame: idx,
+                nframes: self.len(),
+            });
+        }
+
+        // later frames only store the chunks that differ from frame 0, so start from a copy
+        let mut snap = if idx == 0 {
+            OwnedSnapshot::default()
+        } else {
+            self.initial_frame()?.clone()
+        };
+
+        let configuration = &mut snap.configuration;
+        self.update_scalar(idx, "configuration/step", &mut configuration.step)?;
+        self.update_scalar(
+            idx,
+            "configuration/dimensions",
+            &mut configuration.dimensions,
+        )?;
+        if self.file.chunk_exists(idx, "configuration/box") {
+            configuration.box_ = read_box(&self.file, idx)?;
+        }
+
+        let particles = &mut snap.particles;
+        self.update_scalar(idx, "particles/N", &mut particles.n)?;
+        self.update(idx, "particles/position", &mut particles.position)?;
+        self.update(idx, "particles/orientation", &mut particles.orientation)?;
+        self.update_flat(idx, "particles/typeid", &mut particles.typeid)?;
+        if self.file.chunk_exists(idx, "particles/types") {
+            particles.types = Some(self.file.read_string_chunk(idx, "particles/types")?);
         }
+        self.update_flat(idx, "particles/mass", &mut particles.mass)?;
+        self.update_flat(idx, "particles/charge", &mut particles.charge)?;
+        self.update_flat(idx, "particles/diameter", &mut particles.diameter)?;
+        self.update_flat(idx, "particles/body", &mut particles.body)?;
+        self.update(
+            idx,
+            "particles/moment_inertia",
+            &mut particles.moment_inertia,
+        )?;
+        self.update(idx, "particles/velocity", &mut particles.velocity)?;
+        self.update(idx, "particles/angmom", &mut particles.angmom)?;
+        self.update(idx, "particles/image", &mut particles.image)?;
 
-        Self::new(file)
+        self.update_bonds(idx, "bonds", &mut snap.bonds)?;
+        self.update_bonds(idx, "angles", &mut snap.angles)?;
+        self.update_bonds(idx, "dihedrals", &mut snap.dihedrals)?;
+        self.update_bonds(idx, "impropers", &mut snap.impropers)?;
+        let constraints = &mut snap.constraints;
+        self.update_scalar(idx, "constraints/N", &mut constraints.n)?;
+        if self.file.chunk_exists(idx, "constraints/value") {
+            constraints.value = self
+                .file
+                .read_chunk_flat(idx, "constraints/value")?
+                .into_raw_vec();
+        }
+        if self.file.chunk_exists(idx, "constraints/group") {
+            constraints.group = self.file.read_chunk(idx, "constraints/group")?;
+        }
+        self.update_bonds(idx, "pairs", &mut snap.pairs)?;
+
+        Ok(snap)
     }
 
-    fn _read_frame(&'a self, idx: usize) -> Snapshot<'a> {
+    /// Frame 0, read on first access and kept for the frames that inherit from it.
+    fn initial_frame(&self) -> Result<Ref<'_, OwnedSnapshot>, GsdError> {
+        if self.initial_frame.borrow().is_none() {
+            let frame = self._read_frame(0)?;
+            *self.initial_frame.borrow_mut() = Some(frame);
+        }
+        Ok(Ref::map(self.initial_frame.borrow(), |frame| {
+            frame.as_ref().expect("initial frame was just read")
+        }))
+    }
 
-        if idx > self.len() {
-            panic!("Index out of bounds!");
+    /// Replace `bonds` with the `{prefix}/*` chunks that frame `idx` stores.
+    fn update_bonds<const M: usize>(
+        &self,
+        idx: usize,
+        prefix: &str,
+        bonds: &mut BondData<M>,
+    ) -> Result<(), GsdError> {
+        self.update_scalar(idx, &format!("{}/N", prefix), &mut bonds.n)?;
+        let name = format!("{}/types", prefix);
+        if self.file.chunk_exists(idx, &name) {
+            bonds.types = self.file.read_string_chunk(idx, &name)?;
+        }
+        let name = format!("{}/typeid", prefix);
+        if self.file.chunk_exists(idx, &name) {
+            bonds.typeid = self.file.read_chunk_flat(idx, &name)?.into_raw_vec();
         }
 
-        if self.initial_frame.is_none() && idx != 0 {
-            self._read_frame(0);
+        let name = format!("{}/group", prefix);
+        if self.file.chunk_exists(idx, &name) {
+            let group = self.file.read_chunk::<u32>(idx, &name)?;
+            if group.ncols() != M {
+                return Err(GsdError::FileCorrupt(format!(
+                    "{} must have {} columns, not {}: {}",
+                    name,
+                    M,
+                    group.ncols(),
+                    self.file.name()
+                )));
+            }
+            bonds.group = group;
         }
+        Ok(())
+    }
 
-        let step: usize = if self.file.chunk_exists(idx, "configuration/step") {
-            self.file.read_chunk_flat(idx, "configuration/step").unwrap()[0]
+    /// Replace `value` with chunk `name` of frame `idx`, when that frame stores it.
+    fn update<T: Clone + num_traits::Num>(
+        &self,
+        idx: usize,
+        name: &str,
+        value: &mut Option<Array2<T>>,
+    ) -> Result<(), GsdError> {
+        if self.file.chunk_exists(idx, name) {
+            *value = Some(self.file.read_chunk(idx, name)?);
         }
-        else {
-            self.file.read_chunk_flat(idx, "configuration/step").unwrap()[0]
-        };
+        Ok(())
+    }
 
+    fn update_flat<T: Clone + num_traits::Num>(
+        &self,
+        idx: usize,
+        name: &str,
+        value: &mut Option<Vec<T>>,
+    ) -> Result<(), GsdError> {
+        if self.file.chunk_exists(idx, name) {
+            *value = Some(self.file.read_chunk_flat(idx, name)?.into_raw_vec());
+        }
+        Ok(())
+    }
 
+    fn update_scalar<T: Clone + num_traits::Num>(
+        &self,
+        idx: usize,
+        name: &str,
+        value: &mut T,
+    ) -> Result<(), GsdError> {
+        if self.file.chunk_exists(idx, name) {
+            *value = self.read_scalar(idx, name)?;
+        }
+        Ok(())
+    }
 
-        Snapshot::default()
+    /// The frame a chunk should be read from for frame `idx`: `idx` itself when the chunk is
+    /// stored there, otherwise frame 0 when it holds the chunk.
+    fn chunk_frame(&self, idx: usize, name: &str) -> Option<usize> {
+        if self.file.chunk_exists(idx, name) {
+            Some(idx)
+        } else if self.file.chunk_exists(0, name) {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    /// Read a single-valued chunk for frame `idx`, falling back to frame 0 and then to
+    /// `default` when neither frame stores it.
+    fn read_scalar_or<T: Clone + num_traits::Num>(
+        &self,
+        idx: usize,
+        name: &str,
+        default: T,
+    ) -> Result<T, GsdError> {
+        match self.chunk_frame(idx, name) {
+            Some(frame) => self.read_scalar(frame, name),
+            None => Ok(default),
+        }
+    }
+
+    /// Read the single value of chunk `name` stored in `frame`.
+    fn read_scalar<T: Clone + num_traits::Num>(
+        &self,
+        frame: usize,
+        name: &str,
+    ) -> Result<T, GsdError> {
+        self.file
+            .read_chunk_flat::<T>(frame, name)?
+            .iter()
+            .next()
+            .cloned()
+            .ok_or_else(|| {
+                GsdError::FileCorrupt(format!("{} is empty: {}", name, self.file.name()))
+            })
+    }
+
+    /// Read frame `idx`. Panics when `idx` is out of range or the frame cannot be read.
+    pub fn index(&self, idx: usize) -> OwnedSnapshot {
+        self._read_frame(idx).unwrap()
+    }
+
+    /// Read frame `idx` by value, returning an error instead of panicking when `idx` is out of
+    /// range or the frame cannot be read.
+    ///
+    /// `HOOMDTrajectory` does not implement `std::ops::Index`: frames are decoded on every
+    /// access, and `Index` could only hand out references by keeping each decoded frame alive
+    /// for as long as the trajectory.
+    pub fn at(&self, idx: usize) -> Result<OwnedSnapshot, GsdError> {
+        self._read_frame(idx)
+    }
+
+    /// Like `index`, but returns `None` when `idx` is out of range.
+    pub fn get(&self, idx: usize) -> Option<OwnedSnapshot> {
+        if idx < self.len() {
+            Some(self.index(idx))
+        } else {
+            None
+        }
     }
 
-    fn index(&'a self, idx: usize) -> Snapshot<'a> {
-        let snap = self._read_frame(idx);
-        snap
+    /// Like `get`, but negative indices count from the end, so `-1` is the last frame.
+    pub fn get_signed(&self, idx: i64) -> Option<OwnedSnapshot> {
+        let idx = if idx < 0 {
+            self.len().checked_sub(idx.unsigned_abs() as usize)?
+        } else {
+            idx as usize
+        };
+        self.get(idx)
     }
 
-    fn view(&'a self, slice: (Range<usize>, usize)) -> HOOMDTrajectoryView<'a> {
+    /// View of the frames in `slice.0`, taking every `slice.1`-th frame. Ranges past the last
+    /// frame are clamped. Panics when the step `slice.1` is 0.
+    pub fn view(&self, slice: (Range<usize>, usize)) -> HOOMDTrajectoryView<'_> {
+        assert!(slice.1 > 0, "view step must be at least 1");
+        let end = slice.0.end.min(self.len());
+        let start = slice.0.start.min(end);
         HOOMDTrajectoryView {
             trajectory: self,
-            slice,
+            slice: (start..end, slice.1),
         }
     }
 
-    fn iter(&'a self) -> HOOMDTrajectoryIterator<'a> {
+    /// View of the frames selected by a Python style slice, `"start:stop:step"`, e.g. `"::2"`
+    /// or `"10:100:5"`. Empty components default to the first frame, the end of the trajectory
+    /// and a step of 1, and negative `start` and `stop` count from the end. Only positive steps
+    /// are supported.
+    pub fn view_str(&self, spec: &str) -> Result<HOOMDTrajectoryView<'_>, GsdError> {
+        Ok(self.view(parse_slice(spec, self.len())?))
+    }
+
+    pub fn iter(&self) -> HOOMDTrajectoryIterator<'_> {
         self.into_iter()
     }
 
-    fn len(&self) -> usize {
+    /// Start building an iteration over some of the frames, see `FrameIter`.
+    pub fn frames(&self) -> FrameIter<'_> {
+        FrameIter {
+            trajectory: self,
+            range: 0..self.len(),
+            stride: 1,
+        }
+    }
+
+    /// Iterate from the last frame to the first.
+    pub fn iter_rev(&self) -> std::iter::Rev<HOOMDTrajectoryIterator<'_>> {
+        self.iter().rev()
+    }
+
+    /// Like `iter`, but yields read errors instead of panicking on them.
+    pub fn try_iter(&self) -> HOOMDTrajectoryTryIterator<'_> {
+        HOOMDTrajectoryTryIterator { inner: self.iter() }
+    }
+
+    /// Number of frames in the trajectory.
+    pub fn len(&self) -> usize {
         self.file.nframes()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Index of the frame whose step is `step`. Steps increase from frame to frame, so the
+    /// frames are binary searched, reading only `configuration/step` of each probed frame.
+    pub fn frame_at_step(&self, step: u64) -> Result<Option<usize>, GsdError> {
+        let idx = self.frames_through_step(step)?;
+        if idx > 0 && self.step(idx - 1)? == step {
+            Ok(Some(idx - 1))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Index of the last frame with a step of at most `step`.
+    pub fn frame_at_or_before_step(&self, step: u64) -> Result<Option<usize>, GsdError> {
+        let idx = self.frames_through_step(step)?;
+        Ok(idx.checked_sub(1))
+    }
+
+    /// Number of leading frames with a step of at most `step`.
+    fn frames_through_step(&self, step: u64) -> Result<usize, GsdError> {
+        let (mut lo, mut hi) = (0, self.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.step(mid)? <= step {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        Ok(lo)
+    }
+
+    fn step(&self, idx: usize) -> Result<u64, GsdError> {
+        self.read_scalar_or(idx, "configuration/step", 0)
+    }
+
+    /// Read every logged quantity of `frame`, keyed by the full chunk name, e.g.
+    /// `log/md/pair/LJ/energy`. Like other chunks, quantities only stored in frame 0 are
+    /// inherited by later frames.
+    pub fn read_log(&self, frame: usize) -> Result<HashMap<String, LogValue>, GsdError> {
+        let mut log = HashMap::new();
+        for name in self.file.find_matching_chunk_names("log/") {
+            let source = match self.chunk_frame(frame, name) {
+                Some(source) => source,
+                None => continue,
+            };
+            let file = &self.file;
+            let value = match file.chunk_info(source, name)?.type_ {
+                GSDType::UINT8 => LogValue::UINT8(file.read_chunk_dyn(source, name)?),
+                GSDType::UINT16 => LogValue::UINT16(file.read_chunk_dyn(source, name)?),
+                GSDType::UINT32 => LogValue::UINT32(file.read_chunk_dyn(source, name)?),
+                GSDType::UINT64 => LogValue::UINT64(file.read_chunk_dyn(source, name)?),
+                GSDType::INT8 => LogValue::INT8(file.read_chunk_dyn(source, name)?),
+                GSDType::INT16 => LogValue::INT16(file.read_chunk_dyn(source, name)?),
+                GSDType::INT32 => LogValue::INT32(file.read_chunk_dyn(source, name)?),
+                GSDType::INT64 => LogValue::INT64(file.read_chunk_dyn(source, name)?),
+                GSDType::FLOAT => LogValue::FLOAT(file.read_chunk_dyn(source, name)?),
+                GSDType::DOUBLE => LogValue::DOUBLE(file.read_chunk_dyn(source, name)?),
+            };
+            log.insert(name.to_owned(), value);
+        }
+        Ok(log)
+    }
+
+    /// Read chunk `name` for `frame`, inheriting it from frame 0 when `frame` does not store it,
+    /// and return the data together with the frame it was read from.
+    pub fn read_chunk_with_source<T: Clone + num_traits::Num>(
+        &self,
+        frame: usize,
+        name: &str,
+    ) -> Result<(Array2<T>, usize), GsdError> {
+        if frame >= self.len() {
+            return Err(GsdError::FrameOutOfRange {
+                frame,
+                nframes: self.len(),
+            });
+        }
+        let source = self
+            .chunk_frame(frame, name)
+            .ok_or_else(|| GsdError::ChunkNotFound {
+                frame,
+                name: name.to_owned(),
+            })?;
+        Ok((self.file.read_chunk(source, name)?, source))
+    }
+
+    /// Iterate over the frames that store chunk `name` themselves, yielding each frame index
+    /// together with its data. Frames that would inherit the chunk from frame 0 are skipped.
+    pub fn frames_with_chunk<'b, T: 'b + Clone + num_traits::Num>(
+        &'b self,
+        name: &'b str,
+    ) -> impl Iterator<Item = Result<(usize, Array2<T>), GsdError>> + 'b {
+        (0..self.len())
+            .filter(move |&frame| self.file.chunk_exists(frame, name))
+            .map(move |frame| Ok((frame, self.file.read_chunk(frame, name)?)))
+    }
+
+    /// Step of every frame, inheriting the step of frame 0 (or 0) where a frame stores none.
+    pub fn steps(&self) -> Result<Vec<u64>, GsdError> {
+        (0..self.len()).map(|idx| self.step(idx)).collect()
+    }
+
+    /// Box of every frame as a `(nframes, 6)` array of `[lx, ly, lz, xy, xz, yz]` rows,
+    /// inheriting the box of frame 0 (or HOOMD's default box) where a frame stores none.
+    pub fn boxes(&self) -> Result<Array2<f32>, GsdError> {
+        let mut boxes = Array2::<f32>::zeros((self.len(), 6));
+        for (idx, mut row) in boxes.outer_iter_mut().enumerate() {
+            let box_ = match self.chunk_frame(idx, "configuration/box") {
+                Some(frame) => read_box(&self.file, frame)?,
+                None => DEFAULT_BOX,
+            };
+            row.assign(&ndarray::ArrayView1::from(&box_));
+        }
+        Ok(boxes)
+    }
+
+    /// Number density `N / V` of every frame. For 2D systems the box area is used in place
+    /// of the volume.
+    pub fn density_series(&self) -> Result<Vec<f32>, GsdError> {
+        (0..self.len())
+            .map(|idx| {
+                let n: u32 = self.read_scalar_or(idx, "particles/N", 0)?;
+                let dimensions: u8 = self.read_scalar_or(idx, "configuration/dimensions", 3)?;
+                let box_ = match self.chunk_frame(idx, "configuration/box") {
+                    Some(frame) => read_box(&self.file, frame)?,
+                    None => DEFAULT_BOX,
+                };
+                Ok(n as f32 / box_volume(&box_, dimensions))
+            })
+            .collect()
+    }
+
+    /// Write `snap` as a new frame. Following HOOMD's convention, later frames only store the
+    /// chunks whose data differs from frame 0, and readers inherit the rest from frame 0.
+    ///
+    /// The configuration and the counts `n` are always set, as they have defaults. Every
+    /// `Option` field and every bond-like group is optional, and is only written when set. A
+    /// field that is set must hold one row per particle (or per group member for bonds, angles,
+    /// dihedrals, impropers, constraints and pairs), which `OwnedSnapshot::validate` checks
+    /// before anything is written.
+    pub fn append(&mut self, snap: &OwnedSnapshot) -> Result<(), GsdError> {
+        snap.validate()?;
+        if self.file.nframes() > 0 && self.initial_frame.get_mut().is_none() {
+            let initial_frame = if !self.file.is_readable() {
+                // files opened for appending cannot be read, so read frame 0 with a new handle
+                let reader = GSDFile::open(self.file.name(), Mode::Read, None)?;
+                HOOMDTrajectory::new(reader)._read_frame(0)?
+            } else {
+                self._read_frame(0)?
+            };
+            *self.initial_frame.get_mut() = Some(initial_frame);
+        }
+        let initial = self.initial_frame.get_mut().as_ref();
+        let file = &mut self.file;
+
+        let configuration = &snap.configuration;
+        let initial_configuration = initial.map(|s| &s.configuration);
+        file.write_chunk("configuration/step", &vec![configuration.step])?;
+        if changed(
+            &configuration.dimensions,
+            initial_configuration.map(|c| &c.dimensions),
+        ) {
+            file.write_chunk("configuration/dimensions", &vec![configuration.dimensions])?;
+        }
+        if changed(&configuration.box_, initial_configuration.map(|c| &c.box_)) {
+            file.write_chunk("configuration/box", &configuration.box_.to_vec())?;
+        }
+
+        write_particles(file, &snap.particles, initial.map(|s| &s.particles))?;
+        write_bonds(file, "bonds", &snap.bonds, initial.map(|s| &s.bonds))?;
+        write_bonds(file, "angles", &snap.angles, initial.map(|s| &s.angles))?;
+        write_bonds(
+            file,
+            "dihedrals",
+            &snap.dihedrals,
+            initial.map(|s| &s.dihedrals),
+        )?;
+        write_bonds(
+            file,
+            "impropers",
+            &snap.impropers,
+            initial.map(|s| &s.impropers),
+        )?;
+        write_constraints(file, &snap.constraints, initial.map(|s| &s.constraints))?;
+        write_bonds(file, "pairs", &snap.pairs, initial.map(|s| &s.pairs))?;
+
+        file.end_frame()?;
+        if self.initial_frame.get_mut().is_none() {
+            *self.initial_frame.get_mut() = Some(snap.clone());
+        }
+        Ok(())
+    }
+
+    /// Write a logged quantity to the frame being built, prefixing `name` with `log/` unless it
+    /// already starts with it. The quantity is stored in the next frame committed, e.g. by
+    /// `append`. Scalars are written as a single value and 1 dimensional arrays as one column.
+    pub fn write_log(&mut self, name: &str, data: LogValue) -> Result<(), GsdError> {
+        let name = if name.starts_with("log/") {
+            name.to_owned()
+        } else {
+            format!("log/{}", name)
+        };
+        let file = &mut self.file;
+        match data {
+            LogValue::UINT8(data) => write_log_array(file, &name, data),
+            LogValue::UINT16(data) => write_log_array(file, &name, data),
+            LogValue::UINT32(data) => write_log_array(file, &name, data),
+            LogValue::UINT64(data) => write_log_array(file, &name, data),
+            LogValue::INT8(data) => write_log_array(file, &name, data),
+            LogValue::INT16(data) => write_log_array(file, &name, data),
+            LogValue::INT32(data) => write_log_array(file, &name, data),
+            LogValue::INT64(data) => write_log_array(file, &name, data),
+            LogValue::FLOAT(data) => write_log_array(file, &name, data),
+            LogValue::DOUBLE(data) => write_log_array(file, &name, data),
+        }
+    }
+
+    /// Copy the trajectory to a new file at `dest` that only names the chunks stored in some
+    /// frame. The namelist of a GSD file never shrinks, so this recovers files whose namelist
+    /// has filled up with names of chunks that are no longer written.
+    pub fn rewrite_pruning_names<P: AsRef<Path>>(&self, dest: P) -> Result<(), GsdError> {
+        let metadata = Metadata {
+            application: self.file.application().to_owned(),
+            schema: self.file.schema().to_owned(),
+            schema_version: self.file.schema_version(),
+        };
+        let mut dest = GSDFile::open(dest, Mode::Write, Some(metadata))?;
+        for frame in 0..self.len() {
+            for name in self.file.chunk_names(frame) {
+                self.file.copy_chunk_to(frame, &name, &mut dest)?;
+            }
+            dest.end_frame()?;
+        }
+        Ok(())
+    }
+
+    /// Write every `stride`-th frame, starting with frame 0, to a new HOOMD file at `output`.
+    /// Frames are written as complete snapshots, so the output does not depend on frames that
+    /// were skipped.
+    pub fn write_thinned<P: AsRef<Path>>(&self, output: P, stride: usize) -> Result<(), GsdError> {
+        self.write_thinned_with_progress(output, stride, |_, _| {})
+    }
+
+    /// Like [`HOOMDTrajectory::write_thinned`], calling `progress` with `(processed, total)`
+    /// after each frame is written.
+    pub fn write_thinned_with_progress<P: AsRef<Path>>(
+        &self,
+        output: P,
+        stride: usize,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<(), GsdError> {
+        if stride == 0 {
+            return Err(GsdError::InvalidArgument(
+                "stride must be at least 1".to_owned(),
+            ));
+        }
+        let metadata = Metadata {
+            application: self.file.application().to_owned(),
+            schema: self.file.schema().to_owned(),
+            schema_version: self.file.schema_version(),
+        };
+        let mut thinned = HOOMDTrajectory::new(GSDFile::open(output, Mode::Write, Some(metadata))?);
+        let view = self.view((0..self.len(), stride));
+        let total = view.len();
+        for (i, snap) in view.try_iter().enumerate() {
+            thinned.append(&snap?)?;
+            progress(i + 1, total);
+        }
+        Ok(())
+    }
+
+    /// Concatenate the trajectories in `inputs`, e.g. the parts of a restarted simulation, into
+    /// a new file at `output`.
+    ///
+    /// Every input must have the schema and schema version of the first, and the same number
+    /// of particles in every frame. Steps must increase from one input to the next; inputs
+    /// whose first step is not past the last step of the previous input are rejected unless
+    /// `allow_overlap` is set.
+    ///
+    /// Frames of later inputs also store the chunks they inherit from their own frame 0, and
+    /// the HOOMD default of every chunk that the output's frame 0 stores but they do not, so
+    /// they do not pick up the values of the first input. Log and custom chunks have no
+    /// default, so frames without them still read those of the output's frame 0.
+    pub fn concat_into(
+        inputs: &[&Path],
+        output: &Path,
+        allow_overlap: bool,
+    ) -> Result<(), GsdError> {
+        Self::concat_into_with_progress(inputs, output, allow_overlap, |_, _| {})
+    }
+
+    /// Like [`HOOMDTrajectory::concat_into`], calling `progress` with `(processed, total)`
+    /// after each frame is copied, where `total` is the number of frames of all inputs.
+    pub fn concat_into_with_progress(
+        inputs: &[&Path],
+        output: &Path,
+        allow_overlap: bool,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<(), GsdError> {
+        let trajectories = inputs
+            .iter()
+            .map(|path| HOOMDTrajectory::try_new(GSDFile::open(path, Mode::Read, None)?))
+            .collect::<Result<Vec<_>, GsdError>>()?;
+        let first = trajectories.first().ok_or_else(|| {
+            GsdError::InvalidArgument("no trajectories to concatenate".to_owned())
+        })?;
+
+        let n: u32 = first.read_scalar_or(0, "particles/N", 0)?;
+        let mut last_step = None;
+        for trajectory in &trajectories {
+            let file = &trajectory.file;
+            if (file.schema(), file.schema_version())
+                != (first.file.schema(), first.file.schema_version())
+            {
+                return Err(GsdError::InvalidArgument(format!(
+                    "schema {} {:?} does not match schema {} {:?} of {}: {}",
+                    file.schema(),
+                    file.schema_version(),
+                    first.file.schema(),
+                    first.file.schema_version(),
+                    first.file.name(),
+                    file.name()
+                )));
+            }
+            for frame in 0..trajectory.len().max(1) {
+                let file_n: u32 = trajectory.read_scalar_or(frame, "particles/N", 0)?;
+                if file_n != n {
+                    return Err(GsdError::InvalidArgument(format!(
+                        "{} particles in frame {} do not match {} particles of {}: {}",
+                        file_n,
+                        frame,
+                        n,
+                        first.file.name(),
+                        file.name()
+                    )));
+                }
+            }
+            if trajectory.is_empty() {
+                continue;
+            }
+            let first_step = trajectory.step(0)?;
+            match last_step {
+                Some(last_step) if first_step <= last_step && !allow_overlap => {
+                    return Err(GsdError::InvalidArgument(format!(
+                        "first step {} overlaps last step {} of the previous trajectory: {}",
+                        first_step,
+                        last_step,
+                        file.name()
+                    )));
+                }
+                _ => {}
+            }
+            last_step = Some(trajectory.step(trajectory.len() - 1)?);
+        }
+
+        let metadata = Metadata {
+            application: first.file.application().to_owned(),
+            schema: first.file.schema().to_owned(),
+            schema_version: first.file.schema_version(),
+        };
+        let mut dest = GSDFile::open(output, Mode::Write, Some(metadata))?;
+        let total = trajectories.iter().map(|t| t.len()).sum();
+        let mut processed = 0;
+        // chunks of the output's frame 0, which frames of later inputs would otherwise inherit
+        let mut initial_names: Option<Vec<String>> = None;
+        for trajectory in &trajectories {
+            let file = &trajectory.file;
+            let inherits_output = initial_names.is_some();
+            for frame in 0..trajectory.len() {
+                let mut names = file.chunk_names(frame);
+                if inherits_output {
+                    names.extend(file.chunk_names(0));
+                    names.extend(initial_names.iter().flatten().cloned());
+                    names.sort();
+                    names.dedup();
+                }
+                for name in &names {
+                    match trajectory.chunk_frame(frame, name) {
+                        Some(source) => file.copy_chunk_to(source, name, &mut dest)?,
+                        None => write_hoomd_default(&mut dest, name, n as usize)?,
+                    }
+                }
+                if initial_names.is_none() {
+                    initial_names = Some(names);
+                }
+                dest.end_frame()?;
+                processed += 1;
+                progress(processed, total);
+            }
+        }
+        Ok(())
+    }
+
+    /// Write each `(N, M)` slice along the leading axis of `data` as chunk `name` of a new
+    /// frame, so that an `(nframes, N, M)` array is stored as `nframes` consecutive frames.
+    pub fn write_stacked(&mut self, name: &str, data: ArrayView3<f32>) -> Result<(), GsdError> {
+        for frame in data.outer_iter() {
+            self.file.write_chunk(name, frame)?;
+            self.file.end_frame()?;
+        }
+        Ok(())
+    }
+
+    /// Iterate over batches of consecutive frames, with as many frames per batch as fit in
+    /// `max_bytes`. The size of a frame is estimated from the chunks stored in frame 0, and
+    /// every batch holds at least one frame. A batch is an error when any of its frames cannot
+    /// be read.
+    pub fn frame_batches_memlimit(
+        &self,
+        max_bytes: usize,
+    ) -> impl Iterator<Item = Result<Vec<OwnedSnapshot>, GsdError>> + '_ {
+        let len = self.len();
+        let frame_bytes: usize = if len == 0 {
+            0
+        } else {
+            self.file
+                .chunk_names(0)
+                .iter()
+                .filter_map(|name| self.file.chunk_info(0, name).ok())
+                .map(|info| info.nbytes())
+                .sum()
+        };
+        let batch_size = (max_bytes / frame_bytes.max(1)).max(1);
+
+        (0..len).step_by(batch_size).map(move |start| {
+            (start..(start + batch_size).min(len))
+                .map(|idx| self.at(idx))
+                .collect()
+        })
+    }
 }
 
-impl<'a> IntoIterator for &'a HOOMDTrajectory<'a> {
-    type Item = Snapshot<'a>;
+impl<'a> IntoIterator for &'a HOOMDTrajectory {
+    type Item = OwnedSnapshot;
     type IntoIter = HOOMDTrajectoryIterator<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -226,3 +1892,65 @@ impl<'a> IntoIterator for &'a HOOMDTrajectory<'a> {
         }
     }
 }
+
+/// Serialize `Array2` fields as nested lists, one list per row, rather than ndarray's own
+/// `{"v", "dim", "data"}` representation.
+#[cfg(feature = "serde")]
+mod nested_list {
+    use ndarray::Array2;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        array: &Array2<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(array.outer_iter().map(|row| row.iter().collect::<Vec<_>>()))
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Array2<T>, D::Error> {
+        let rows = Vec::<Vec<T>>::deserialize(deserializer)?;
+        let shape = (rows.len(), rows.first().map_or(0, Vec::len));
+        if rows.iter().any(|row| row.len() != shape.1) {
+            return Err(D::Error::custom(
+                "rows of an array must have the same length",
+            ));
+        }
+        Array2::from_shape_vec(shape, rows.into_iter().flatten().collect())
+            .map_err(D::Error::custom)
+    }
+
+    pub mod option {
+        use ndarray::Array2;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        struct Rows<'a, T>(&'a Array2<T>);
+
+        impl<T: Serialize> Serialize for Rows<'_, T> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                super::serialize(self.0, serializer)
+            }
+        }
+
+        #[derive(Deserialize)]
+        #[serde(bound = "T: Deserialize<'de>")]
+        struct Owned<T>(#[serde(with = "super")] Array2<T>);
+
+        pub fn serialize<T: Serialize, S: Serializer>(
+            array: &Option<Array2<T>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match array {
+                Some(array) => serializer.serialize_some(&Rows(array)),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Array2<T>>, D::Error> {
+            Ok(Option::<Owned<T>>::deserialize(deserializer)?.map(|Owned(array)| array))
+        }
+    }
+}
@@ -4,8 +4,8 @@ pub mod hoomd;
 mod tests;
 
 #[repr(u8)]
-#[derive(Debug, PartialEq)]
-enum GSDType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GSDType {
     UINT8 = 1,
     UINT16,
     UINT32,
@@ -19,33 +19,115 @@ enum GSDType {
 }
 
 impl GSDType {
-    fn from_type<T>() -> Self {
+    /// The GSD type that stores elements of `T`.
+    ///
+    /// GSD only defines the fixed width integer types, `f32` and `f64`. Other element types,
+    /// including half precision floats such as `half::f16`, are rejected with
+    /// `GsdError::UnsupportedType`; store half precision data by its `u16` bit pattern instead.
+    pub fn try_from_type<T>() -> Result<Self, GsdError> {
         let type_name = std::any::type_name::<T>();
         match type_name {
-            "u8" => GSDType::UINT8,
-            "u16" => GSDType::UINT16,
-            "u32" => GSDType::UINT32,
-            "u64" => GSDType::UINT64,
-            "i8" => GSDType::INT8,
-            "i16" => GSDType::INT16,
-            "i32" => GSDType::INT32,
-            "i64" => GSDType::INT64,
-            "f32" => GSDType::FLOAT,
-            "f64" => GSDType::DOUBLE,
-            _ => panic!("Unsupported type"),
+            "u8" => Ok(GSDType::UINT8),
+            "u16" => Ok(GSDType::UINT16),
+            "u32" => Ok(GSDType::UINT32),
+            "u64" => Ok(GSDType::UINT64),
+            "i8" => Ok(GSDType::INT8),
+            "i16" => Ok(GSDType::INT16),
+            "i32" => Ok(GSDType::INT32),
+            "i64" => Ok(GSDType::INT64),
+            "f32" => Ok(GSDType::FLOAT),
+            "f64" => Ok(GSDType::DOUBLE),
+            _ => Err(GsdError::UnsupportedType {
+                type_name: type_name.to_owned(),
+            }),
         }
     }
 
-    fn check_match<T>(&self) -> Result<(), String> {
-        let check_type = Self::from_type::<T>();
+    pub fn size_bytes(&self) -> usize {
+        match self {
+            GSDType::UINT8 | GSDType::INT8 => 1,
+            GSDType::UINT16 | GSDType::INT16 => 2,
+            GSDType::UINT32 | GSDType::INT32 | GSDType::FLOAT => 4,
+            GSDType::UINT64 | GSDType::INT64 | GSDType::DOUBLE => 8,
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, GSDType::FLOAT | GSDType::DOUBLE)
+    }
+
+    /// Checked conversion from the raw type byte stored in an index entry.
+    pub fn from_raw(raw: u8) -> Option<Self> {
+        match raw {
+            1 => Some(GSDType::UINT8),
+            2 => Some(GSDType::UINT16),
+            3 => Some(GSDType::UINT32),
+            4 => Some(GSDType::UINT64),
+            5 => Some(GSDType::INT8),
+            6 => Some(GSDType::INT16),
+            7 => Some(GSDType::INT32),
+            8 => Some(GSDType::INT64),
+            9 => Some(GSDType::FLOAT),
+            10 => Some(GSDType::DOUBLE),
+            _ => None,
+        }
+    }
+
+    fn check_match<T>(&self) -> Result<(), GsdError> {
+        let check_type = Self::try_from_type::<T>()?;
         if *self != check_type {
-            Err(format!("Type mismatch: {:?} != {:?}", self, check_type))
+            Err(GsdError::TypeMismatch {
+                expected: check_type,
+                found: *self,
+            })
         } else {
             Ok(())
         }
     }
 }
 
+/// Prints the lowercase type names of the GSD specification, such as `uint8` and `double`.
+impl std::fmt::Display for GSDType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            GSDType::UINT8 => "uint8",
+            GSDType::UINT16 => "uint16",
+            GSDType::UINT32 => "uint32",
+            GSDType::UINT64 => "uint64",
+            GSDType::INT8 => "int8",
+            GSDType::INT16 => "int16",
+            GSDType::INT32 => "int32",
+            GSDType::INT64 => "int64",
+            GSDType::FLOAT => "float",
+            GSDType::DOUBLE => "double",
+        })
+    }
+}
+
+/// Parses the type names printed by `Display`.
+impl std::str::FromStr for GSDType {
+    type Err = GsdError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "uint8" => Ok(GSDType::UINT8),
+            "uint16" => Ok(GSDType::UINT16),
+            "uint32" => Ok(GSDType::UINT32),
+            "uint64" => Ok(GSDType::UINT64),
+            "int8" => Ok(GSDType::INT8),
+            "int16" => Ok(GSDType::INT16),
+            "int32" => Ok(GSDType::INT32),
+            "int64" => Ok(GSDType::INT64),
+            "float" => Ok(GSDType::FLOAT),
+            "double" => Ok(GSDType::DOUBLE),
+            _ => Err(GsdError::InvalidArgument(format!(
+                "unknown GSD type {}",
+                name
+            ))),
+        }
+    }
+}
+
 #[repr(u8)]
 enum OpenFlag {
     Readwrite = 1,
@@ -53,22 +135,61 @@ enum OpenFlag {
     Append = 3,
 }
 
-// TODO - Use Error variants for better error handling
-#[allow(dead_code)]
-#[repr(i32)]
-enum GSDResult {
-    Success = 0,
-    IO = -1,
-    InvalidArgument = -2,
-    NotAFile = -3,
-    InvalidFileVersion = -4,
-    FileCorrupt = -5,
-    MemoryAllocFailed = -6,
-    NamelistFull = -7,
-    NotWritable = -8,
-    NotReadable = -9,
+/// Errors returned by this crate. The first variants mirror the error codes of the GSD C API
+/// and carry the name of the file (or other context) the error was raised for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GsdError {
+    IO(String),
+    InvalidArgument(String),
+    NotAFile(String),
+    InvalidFileVersion(String),
+    FileCorrupt(String),
+    MemoryAllocFailed(String),
+    NamelistFull(String),
+    NotWritable(String),
+    NotReadable(String),
+    Unknown(i32, String),
+    TypeMismatch { expected: GSDType, found: GSDType },
+    ChunkNotFound { frame: usize, name: String },
+    FrameOutOfRange { frame: usize, nframes: usize },
+    UnsupportedType { type_name: String },
+    AlreadyExists(String),
 }
 
+impl std::fmt::Display for GsdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GsdError::IO(extra) => write!(f, "I/O error: {}", extra),
+            GsdError::InvalidArgument(extra) => write!(f, "Invalid gsd argument: {}", extra),
+            GsdError::NotAFile(extra) => write!(f, "Not a GSD file: {}", extra),
+            GsdError::InvalidFileVersion(extra) => {
+                write!(f, "Unsupported GSD file version: {}", extra)
+            }
+            GsdError::FileCorrupt(extra) => write!(f, "Corrupt GSD file: {}", extra),
+            GsdError::MemoryAllocFailed(extra) => write!(f, "Memory allocation failed: {}", extra),
+            GsdError::NamelistFull(extra) => write!(f, "GSD namelist is full: {}", extra),
+            GsdError::NotWritable(extra) => write!(f, "File must be writable: {}", extra),
+            GsdError::NotReadable(extra) => write!(f, "File must be readable: {}", extra),
+            GsdError::Unknown(retval, extra) => write!(f, "Unknown error ({}): {}", retval, extra),
+            GsdError::TypeMismatch { expected, found } => {
+                write!(f, "Type mismatch: {} != {}", expected, found)
+            }
+            GsdError::ChunkNotFound { frame, name } => {
+                write!(f, "frame {} / chunk {} not found", frame, name)
+            }
+            GsdError::FrameOutOfRange { frame, nframes } => {
+                write!(f, "frame {} out of range for {} frames", frame, nframes)
+            }
+            GsdError::UnsupportedType { type_name } => {
+                write!(f, "unsupported chunk element type {}", type_name)
+            }
+            GsdError::AlreadyExists(extra) => write!(f, "File already exists: {}", extra),
+        }
+    }
+}
+
+impl std::error::Error for GsdError {}
+
 // we can't have two macros with the name "open", so we'll use C-style namespacing to call this macro
 #[macro_export]
 macro_rules! hoomd_open {
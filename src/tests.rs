@@ -1,16 +1,17 @@
 #![cfg(test)]
 
-use crate::{fl, hoomd, hoomd_open};
+use crate::{fl, hoomd, hoomd_open, GSDType, GsdError};
 use gsd_sys::*;
 use std::env::temp_dir;
 use std::ffi::CString;
 use std::fs::remove_file;
 use std::io::ErrorKind;
 
-fn get_test_file_name() -> String {
+fn get_test_file_name(name: &str) -> String {
     let rusty_fname = format!(
-        "{}/test_gsd_fl.gsd",
-        temp_dir().into_os_string().into_string().unwrap()
+        "{}/test_gsd_{}.gsd",
+        temp_dir().into_os_string().into_string().unwrap(),
+        name
     );
     rusty_fname
 }
@@ -29,7 +30,7 @@ fn safely_remove_file_if_exists(file: &str) {
 
 #[test]
 fn create_and_remove_file() {
-    let rusty_fname = get_test_file_name();
+    let rusty_fname = get_test_file_name("create_and_remove_file");
 
     safely_remove_file_if_exists(&rusty_fname);
 
@@ -53,7 +54,7 @@ fn create_and_remove_file() {
 
 #[test]
 fn fl_module_api() {
-    let rusty_fname = get_test_file_name();
+    let rusty_fname = get_test_file_name("fl_module_api");
 
     let mut gsd_file =
         fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
@@ -84,7 +85,3199 @@ fn fl_module_api() {
 
 #[test]
 fn hoomd_module_api() {
-    let rusty_fname = get_test_file_name();
+    let rusty_fname = get_test_file_name("hoomd_module_api");
 
     let mut hoomd_file = hoomd_open!(&rusty_fname);
-}
\ No newline at end of file
+}
+
+#[test]
+fn fl_chunk_info() {
+    let rusty_fname = get_test_file_name("fl_chunk_info");
+
+    let mut gsd_file =
+        fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
+
+    gsd_file
+        .write_chunk("chunk1", &ndarray::Array2::<f64>::zeros((3, 2)))
+        .unwrap();
+    gsd_file.write_chunk("chunk2", &vec![1u8, 2]).unwrap();
+    gsd_file.end_frame().unwrap();
+    gsd_file.write_chunk("chunk2", &vec![3u8]).unwrap();
+    gsd_file.end_frame().unwrap();
+
+    let info = gsd_file.chunk_info(0, "chunk1").unwrap();
+    assert_eq!(info.type_, GSDType::DOUBLE);
+    assert_eq!((info.n, info.m), (3, 2));
+    assert_eq!(info.nbytes(), 48);
+    assert!(gsd_file.chunk_info(1, "chunk1").is_err());
+
+    assert_eq!(gsd_file.chunk_names(0), vec!["chunk1", "chunk2"]);
+    assert_eq!(gsd_file.chunk_names(1), vec!["chunk2"]);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn invalid_chunk_type_is_corrupt() {
+    let mut index_entry: gsd_index_entry = unsafe { std::mem::zeroed() };
+
+    index_entry.type_ = 9;
+    assert_eq!(fl::entry_type(&index_entry, "test"), Ok(GSDType::FLOAT));
+
+    for raw in [0u8, 11, 255] {
+        index_entry.type_ = raw;
+        assert!(matches!(
+            fl::entry_type(&index_entry, "test"),
+            Err(GsdError::FileCorrupt(_))
+        ));
+    }
+}
+
+#[test]
+fn hoomd_read_double_precision_box() {
+    let rusty_fname = get_test_file_name("hoomd_read_double_precision_box");
+
+    let mut gsd_file = fl::open!(&rusty_fname, "wb+", "other writer", "hoomd", (1, 4)).unwrap();
+
+    gsd_file
+        .write_chunk(
+            "configuration/box",
+            &vec![10.0f64, 20.0, 30.0, 0.5, 0.0, 0.25],
+        )
+        .unwrap();
+    gsd_file.end_frame().unwrap();
+    gsd_file
+        .write_chunk("configuration/box", &vec![1.0f32, 2.0, 3.0, 0.0, 0.0, 0.0])
+        .unwrap();
+    gsd_file.end_frame().unwrap();
+
+    assert_eq!(
+        hoomd::read_box(&gsd_file, 0).unwrap(),
+        [10.0f32, 20.0, 30.0, 0.5, 0.0, 0.25]
+    );
+    assert_eq!(
+        hoomd::read_box(&gsd_file, 1).unwrap(),
+        [1.0f32, 2.0, 3.0, 0.0, 0.0, 0.0]
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_chunk_nd_round_trip() {
+    let rusty_fname = get_test_file_name("fl_chunk_nd_round_trip");
+
+    let mut gsd_file =
+        fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
+
+    let stress =
+        ndarray::Array3::<f32>::from_shape_fn((2, 3, 3), |(i, j, k)| (i * 9 + j * 3 + k) as f32);
+    gsd_file.write_chunk_nd("stress", &stress).unwrap();
+    gsd_file.end_frame().unwrap();
+
+    let info = gsd_file.chunk_info(0, "stress").unwrap();
+    assert_eq!((info.n, info.m), (2, 9));
+    assert_eq!(
+        gsd_file.read_chunk_flat::<u64>(0, "stress/shape").unwrap(),
+        ndarray::Array1::from(vec![2u64, 3, 3])
+    );
+
+    let output = gsd_file.read_chunk_nd::<f32>(0, "stress").unwrap();
+    assert_eq!(output, stress.into_dyn());
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_write_stacked() {
+    let rusty_fname = get_test_file_name("hoomd_write_stacked");
+
+    let positions =
+        ndarray::Array3::<f32>::from_shape_fn((3, 2, 3), |(i, j, k)| (i * 6 + j * 3 + k) as f32);
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file
+            .write_stacked("particles/position", positions.view())
+            .unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(gsd_file.nframes(), 3);
+    for frame in 0..3 {
+        let output = gsd_file
+            .read_chunk::<f32>(frame, "particles/position")
+            .unwrap();
+        assert_eq!(output, positions.index_axis(ndarray::Axis(0), frame));
+    }
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_read_chunk_into() {
+    let rusty_fname = get_test_file_name("fl_read_chunk_into");
+
+    let mut gsd_file =
+        fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
+
+    for frame in 0..3 {
+        let data = ndarray::Array2::<f32>::from_elem((2, 3), frame as f32);
+        gsd_file.write_chunk("chunk1", &data).unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let mut out = ndarray::Array2::<f32>::zeros((2, 3));
+    for frame in 0..3 {
+        gsd_file
+            .read_chunk_into(frame, "chunk1", &mut out.view_mut())
+            .unwrap();
+        assert_eq!(out, ndarray::Array2::from_elem((2, 3), frame as f32));
+    }
+
+    let mut wrong_shape = ndarray::Array2::<f32>::zeros((3, 2));
+    assert!(matches!(
+        gsd_file.read_chunk_into(0, "chunk1", &mut wrong_shape.view_mut()),
+        Err(GsdError::InvalidArgument(_))
+    ));
+    let mut wrong_type = ndarray::Array2::<f64>::zeros((2, 3));
+    assert!(matches!(
+        gsd_file.read_chunk_into(0, "chunk1", &mut wrong_type.view_mut()),
+        Err(GsdError::TypeMismatch { .. })
+    ));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_string_chunk_round_trip() {
+    let rusty_fname = get_test_file_name("fl_string_chunk_round_trip");
+
+    let mut gsd_file =
+        fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
+
+    gsd_file
+        .write_string_chunk("particles/types", &["A", "B", "solvent"])
+        .unwrap();
+    gsd_file.end_frame().unwrap();
+
+    let info = gsd_file.chunk_info(0, "particles/types").unwrap();
+    assert_eq!(info.type_, GSDType::INT8);
+    assert_eq!((info.n, info.m), (3, 8));
+    assert_eq!(
+        gsd_file.read_string_chunk(0, "particles/types").unwrap(),
+        vec!["A", "B", "solvent"]
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_try_iter_stops_on_error() {
+    let rusty_fname = get_test_file_name("hoomd_try_iter_stops_on_error");
+
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file
+            .write_chunk("configuration/step", &vec![0u64])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+        // a step stored with the wrong type cannot be read back
+        gsd_file
+            .write_chunk("configuration/step", &vec![10.0f32])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+        gsd_file
+            .write_chunk("configuration/step", &vec![20u64])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let mut iter = hoomd_file.try_iter();
+    assert!(matches!(iter.next(), Some(Ok(_))));
+    assert!(matches!(
+        iter.next(),
+        Some(Err(GsdError::TypeMismatch { .. }))
+    ));
+    assert!(iter.next().is_none());
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_density_series() {
+    let rusty_fname = get_test_file_name("hoomd_density_series");
+
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file.write_chunk("particles/N", &vec![4u32]).unwrap();
+        gsd_file
+            .write_chunk("configuration/dimensions", &vec![2u8])
+            .unwrap();
+        gsd_file
+            .write_chunk("configuration/box", &vec![2.0f32, 4.0, 1.0, 0.0, 0.0, 0.0])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+        gsd_file
+            .write_chunk("configuration/box", &vec![4.0f32, 4.0, 1.0, 0.5, 0.0, 0.0])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    assert_eq!(hoomd_file.density_series().unwrap(), vec![0.5, 0.25]);
+    drop(hoomd_file);
+
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file.write_chunk("particles/N", &vec![8u32]).unwrap();
+        gsd_file
+            .write_chunk("configuration/box", &vec![2.0f32, 2.0, 4.0, 0.0, 0.0, 0.0])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    assert_eq!(hoomd_file.density_series().unwrap(), vec![0.5]);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_read_chunk_as() {
+    let rusty_fname = get_test_file_name("fl_read_chunk_as");
+
+    let mut gsd_file =
+        fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
+
+    gsd_file
+        .write_chunk("particles/diameter", &vec![0.5f64, 1.0, 2.0])
+        .unwrap();
+    gsd_file.write_chunk("image", &vec![-1i32, 0, 3]).unwrap();
+    gsd_file.write_chunk("count", &vec![3u16, 300]).unwrap();
+    gsd_file.end_frame().unwrap();
+
+    assert_eq!(
+        gsd_file
+            .read_chunk_as::<f32>(0, "particles/diameter")
+            .unwrap(),
+        ndarray::Array2::from(vec![[0.5f32], [1.0], [2.0]])
+    );
+    assert_eq!(
+        gsd_file.read_chunk_as::<i64>(0, "image").unwrap(),
+        ndarray::Array2::from(vec![[-1i64], [0], [3]])
+    );
+    assert_eq!(
+        gsd_file.read_chunk_as::<u16>(0, "count").unwrap(),
+        gsd_file.read_chunk::<u16>(0, "count").unwrap()
+    );
+
+    // 300 does not fit in a u8
+    assert!(matches!(
+        gsd_file.read_chunk_as::<u8>(0, "count"),
+        Err(GsdError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        gsd_file.read_chunk_as::<u8>(0, "particles/diameter"),
+        Err(GsdError::TypeMismatch { .. })
+    ));
+    // the strict reader still refuses any mismatch
+    assert!(gsd_file.read_chunk::<f32>(0, "particles/diameter").is_err());
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_nframes_is_cached() {
+    let rusty_fname = get_test_file_name("fl_nframes_is_cached");
+
+    let mut gsd_file =
+        fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
+    assert_eq!(gsd_file.nframes(), 0);
+
+    for frame in 0..3 {
+        gsd_file.write_chunk("chunk1", &vec![frame as f32]).unwrap();
+        gsd_file.end_frame().unwrap();
+        assert_eq!(gsd_file.nframes(), frame + 1);
+    }
+
+    // the count can be queried while holding references into the file
+    let names = gsd_file.find_matching_chunk_names("chunk");
+    assert_eq!((names.len(), gsd_file.nframes()), (1, 3));
+
+    assert_eq!(gsd_file.truncate().unwrap(), 3);
+    assert_eq!(gsd_file.nframes(), 0);
+    assert_eq!(gsd_file.truncate().unwrap(), 0);
+    drop(gsd_file);
+
+    let mut gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(gsd_file.nframes(), 0);
+    assert!(!gsd_file.is_writable());
+    assert!(matches!(gsd_file.truncate(), Err(GsdError::NotWritable(_))));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_angular_velocities() {
+    let half = std::f32::consts::FRAC_1_SQRT_2;
+    // the second particle is rotated by 90 degrees about z and spins about its body x axis
+    let orientation = ndarray::arr2(&[[1.0f32, 0.0, 0.0, 0.0], [half, 0.0, 0.0, half]]);
+    let angmom = ndarray::arr2(&[[0.0f32, 2.0, 4.0, 6.0], [0.0, 2.0 * half, 2.0 * half, 0.0]]);
+    let moment_inertia = ndarray::arr2(&[[1.0f32, 2.0, 0.0], [2.0, 1.0, 1.0]]);
+
+    let mut snap = hoomd::OwnedSnapshot::default();
+    assert!(snap.angular_velocities().is_none());
+
+    snap.particles = hoomd::ParticleData {
+        n: 2,
+        orientation: Some(orientation),
+        angmom: Some(angmom),
+        moment_inertia: Some(moment_inertia),
+        ..Default::default()
+    };
+
+    let omega = snap.angular_velocities().unwrap();
+    let expected = ndarray::arr2(&[[1.0f32, 1.0, 0.0], [0.5, 0.0, 0.0]]);
+    assert_eq!(omega.dim(), (2, 3));
+    for (a, b) in omega.iter().zip(expected.iter()) {
+        assert!((a - b).abs() < 1e-6, "{} != {}", a, b);
+    }
+}
+
+#[test]
+fn fl_open_with_mode() {
+    use fl::{Metadata, Mode};
+
+    let rusty_fname = get_test_file_name("fl_open_with_mode");
+
+    for mode in [
+        Mode::Write,
+        Mode::ReadWrite,
+        Mode::Read,
+        Mode::ReadWriteTruncate,
+        Mode::ExclusiveWrite,
+        Mode::ExclusiveReadWrite,
+        Mode::Append,
+    ] {
+        assert_eq!(mode.as_str().parse::<Mode>(), Ok(mode));
+    }
+    assert!(matches!(
+        "r".parse::<Mode>(),
+        Err(GsdError::InvalidArgument(_))
+    ));
+
+    // creating a file requires metadata for its header
+    assert!(matches!(
+        fl::GSDFile::open(&rusty_fname, Mode::ReadWriteTruncate, None),
+        Err(GsdError::InvalidArgument(_))
+    ));
+
+    let metadata = Metadata {
+        application: "My application".to_owned(),
+        schema: "My Schema".to_owned(),
+        schema_version: (1, 2),
+    };
+    let mut gsd_file =
+        fl::GSDFile::open(&rusty_fname, Mode::ReadWriteTruncate, Some(metadata)).unwrap();
+    assert_eq!(gsd_file.mode(), "wb+");
+    gsd_file.write_chunk("chunk1", &vec![1u32, 2]).unwrap();
+    gsd_file.end_frame().unwrap();
+    drop(gsd_file);
+
+    let gsd_file = fl::GSDFile::open(&rusty_fname, Mode::Read, None).unwrap();
+    assert_eq!(gsd_file.mode(), "rb");
+    assert_eq!(gsd_file.application(), "My application");
+    assert_eq!(gsd_file.schema(), "My Schema");
+    assert_eq!(gsd_file.schema_version(), (1, 2));
+
+    let header = gsd_file.header();
+    assert_eq!(header.application, "My application");
+    assert_eq!(header.schema, "My Schema");
+    assert_eq!(header.schema_version, (1, 2));
+    assert_eq!(header.raw_schema_version, 1 << 16 | 2);
+    assert_eq!(header.gsd_version, gsd_file.gsd_version());
+    assert_eq!(
+        header.raw_gsd_version,
+        header.gsd_version.0 << 16 | header.gsd_version.1
+    );
+
+    assert_eq!(
+        gsd_file.read_chunk_flat::<u32>(0, "chunk1").unwrap(),
+        ndarray::Array1::from(vec![1u32, 2])
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_duplicate_chunk_in_frame() {
+    let rusty_fname = get_test_file_name("fl_duplicate_chunk_in_frame");
+
+    let mut gsd_file =
+        fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
+
+    gsd_file.write_chunk("chunk1", &vec![1.0f32]).unwrap();
+    assert!(matches!(
+        gsd_file.write_chunk("chunk1", &vec![2.0f32]),
+        Err(GsdError::InvalidArgument(_))
+    ));
+    gsd_file.end_frame().unwrap();
+
+    // the same name may be written again once the frame has ended
+    gsd_file.write_chunk("chunk1", &vec![3.0f32]).unwrap();
+    gsd_file.end_frame().unwrap();
+
+    assert_eq!(
+        gsd_file.read_chunk_flat::<f32>(0, "chunk1").unwrap(),
+        ndarray::Array1::from(vec![1.0f32])
+    );
+    assert_eq!(gsd_file.validate(), Ok(()));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_read_chunk_with_source() {
+    let rusty_fname = get_test_file_name("hoomd_read_chunk_with_source");
+
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file
+            .write_chunk("particles/mass", &vec![1.0f32])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+        gsd_file
+            .write_chunk("configuration/step", &vec![1u64])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+        gsd_file
+            .write_chunk("particles/mass", &vec![2.0f32])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    for (frame, (mass, source)) in [(0, (1.0f32, 0)), (1, (1.0, 0)), (2, (2.0, 2))] {
+        let (data, found) = hoomd_file
+            .read_chunk_with_source::<f32>(frame, "particles/mass")
+            .unwrap();
+        assert_eq!(data, ndarray::Array2::from(vec![[mass]]));
+        assert_eq!(found, source);
+    }
+    assert!(matches!(
+        hoomd_file.read_chunk_with_source::<u64>(2, "configuration/step"),
+        Err(GsdError::ChunkNotFound { frame: 2, .. })
+    ));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_read_particles() {
+    let rusty_fname = get_test_file_name("hoomd_read_particles");
+
+    let position = ndarray::arr2(&[[0.0f32, 1.0, 2.0], [-1.0, -2.0, -3.0]]);
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file.write_chunk("particles/N", &vec![2u32]).unwrap();
+        gsd_file
+            .write_chunk("particles/position", &position)
+            .unwrap();
+        gsd_file
+            .write_chunk("particles/typeid", &vec![0u32, 1])
+            .unwrap();
+        gsd_file
+            .write_chunk("particles/mass", &vec![1.0f32, 2.0])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+        gsd_file
+            .write_chunk("particles/mass", &vec![3.0f32, 4.0])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let snaps: Vec<hoomd::OwnedSnapshot> = hoomd_file
+        .try_iter()
+        .collect::<Result<_, GsdError>>()
+        .unwrap();
+    assert_eq!(snaps.len(), 2);
+
+    for snap in &snaps {
+        assert_eq!(snap.particles.n, 2);
+        assert_eq!(snap.particles.position.as_ref(), Some(&position));
+        assert_eq!(snap.particles.typeid, Some(vec![0, 1]));
+        assert!(snap.particles.orientation.is_none());
+        assert!(snap.particles.charge.is_none());
+    }
+    assert_eq!(snaps[0].particles.mass, Some(vec![1.0, 2.0]));
+    assert_eq!(snaps[1].particles.mass, Some(vec![3.0, 4.0]));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_read_configuration() {
+    let rusty_fname = get_test_file_name("hoomd_read_configuration");
+
+    let box_ = [4.0f32, 5.0, 6.0, 0.1, 0.2, 0.3];
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file
+            .write_chunk("configuration/box", &box_.to_vec())
+            .unwrap();
+        gsd_file
+            .write_chunk("configuration/dimensions", &vec![2u8])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+        gsd_file
+            .write_chunk("configuration/step", &vec![100u64])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let snaps: Vec<hoomd::OwnedSnapshot> = hoomd_file
+        .try_iter()
+        .collect::<Result<_, GsdError>>()
+        .unwrap();
+
+    assert_eq!(snaps[0].configuration.step(), 0);
+    assert_eq!(snaps[1].configuration.step(), 100);
+    for snap in &snaps {
+        assert_eq!(snap.configuration.box_(), box_);
+        assert_eq!(snap.configuration.dimensions(), 2);
+    }
+
+    let default = hoomd::ConfigurationData::default();
+    assert_eq!(default.box_(), [1.0, 1.0, 1.0, 0.0, 0.0, 0.0]);
+    assert_eq!(default.dimensions(), 3);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_read_chunk_f32() {
+    let rusty_fname = get_test_file_name("fl_read_chunk_f32");
+
+    let mut gsd_file =
+        fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
+
+    gsd_file
+        .write_chunk("particles/image", &ndarray::arr2(&[[-1i32, 0, 2]]))
+        .unwrap();
+    gsd_file.write_chunk("value", &vec![0.5f64, -1.5]).unwrap();
+    gsd_file.end_frame().unwrap();
+
+    assert_eq!(
+        gsd_file.read_chunk_f32(0, "particles/image").unwrap(),
+        ndarray::arr2(&[[-1.0f32, 0.0, 2.0]])
+    );
+    assert_eq!(
+        gsd_file.read_chunk_f32(0, "value").unwrap(),
+        ndarray::arr2(&[[0.5f32], [-1.5]])
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_try_new_checks_schema() {
+    let rusty_fname = get_test_file_name("hoomd_try_new_checks_schema");
+
+    for (schema, version, ok) in [
+        ("hoomd", (1, 4), true),
+        ("hoomd", (2, 0), false),
+        ("not hoomd", (1, 4), false),
+    ] {
+        {
+            let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", schema, version).unwrap();
+            gsd_file.end_frame().unwrap();
+        }
+        let result = hoomd::HOOMDTrajectory::try_new(fl::open!(&rusty_fname, "rb").unwrap());
+        assert_eq!(result.is_ok(), ok, "{} {:?}", schema, version);
+    }
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_rewrite_pruning_names() {
+    let rusty_fname = get_test_file_name("hoomd_rewrite_pruning_names");
+    let pruned_fname = get_test_file_name("hoomd_rewrite_pruning_names_pruned");
+
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file.write_chunk("particles/N", &vec![2u32]).unwrap();
+        gsd_file.write_chunk("log/value", &vec![1.5f64]).unwrap();
+        gsd_file.end_frame().unwrap();
+        gsd_file
+            .write_chunk("particles/typeid", &vec![0u32, 1])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    hoomd_file.rewrite_pruning_names(&pruned_fname).unwrap();
+
+    let pruned = fl::open!(&pruned_fname, "rb").unwrap();
+    assert_eq!(pruned.nframes(), 2);
+    assert_eq!(pruned.schema(), "hoomd");
+    assert_eq!(pruned.chunk_names(0), vec!["log/value", "particles/N"]);
+    assert_eq!(pruned.chunk_names(1), vec!["particles/typeid"]);
+    assert_eq!(
+        pruned.read_chunk_flat::<f64>(0, "log/value").unwrap(),
+        ndarray::Array1::from(vec![1.5])
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+    safely_remove_file_if_exists(&pruned_fname);
+}
+
+#[test]
+fn hoomd_append_round_trip() {
+    let rusty_fname = get_test_file_name("hoomd_append_round_trip");
+
+    let mut first = hoomd::OwnedSnapshot::default();
+    first.particles.n = 2;
+    first.particles.position = Some(ndarray::arr2(&[[0.0f32, 1.0, 2.0], [3.0, 4.0, 5.0]]));
+    first.particles.typeid = Some(vec![0, 1]);
+    first.particles.types = Some(vec!["A".to_owned(), "B".to_owned()]);
+    first.particles.mass = Some(vec![1.0, 2.0]);
+    let mut second = first.clone();
+    second.particles.position = Some(ndarray::arr2(&[[0.5f32, 1.5, 2.5], [3.5, 4.5, 5.5]]));
+
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file.append(&first).unwrap();
+        hoomd_file.append(&second).unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert!(gsd_file.chunk_exists(1, "particles/position"));
+    assert!(!gsd_file.chunk_exists(1, "particles/typeid"));
+    assert!(!gsd_file.chunk_exists(1, "particles/mass"));
+    assert!(gsd_file.chunk_exists_effective(1, "particles/mass"));
+    assert!(!gsd_file.chunk_exists_effective(1, "particles/charge"));
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(gsd_file);
+    let snaps: Vec<hoomd::OwnedSnapshot> = hoomd_file
+        .try_iter()
+        .collect::<Result<_, GsdError>>()
+        .unwrap();
+    assert_eq!(snaps, vec![first, second]);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_body_round_trip() {
+    let rusty_fname = get_test_file_name("hoomd_body_round_trip");
+    let thinned_fname = get_test_file_name("hoomd_body_round_trip_thinned");
+
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 3;
+    // particles 0 and 1 form rigid body 0, particle 2 is free
+    snap.particles.body = Some(vec![0, 0, -1]);
+    let mut moved = snap.clone();
+    moved.particles.body = Some(vec![-1, -1, -1]);
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file.append(&snap).unwrap();
+        hoomd_file.append(&moved).unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let bodies: Vec<_> = hoomd_file
+        .try_iter()
+        .map(|snap| snap.unwrap().particles.body)
+        .collect();
+    assert_eq!(bodies, vec![Some(vec![0, 0, -1]), Some(vec![-1, -1, -1])]);
+
+    // rewriting frames keeps the bodies
+    hoomd_file.write_thinned(&thinned_fname, 1).unwrap();
+    let thinned = hoomd::HOOMDTrajectory::new(fl::open!(&thinned_fname, "rb").unwrap());
+    let thinned_bodies: Vec<_> = thinned
+        .try_iter()
+        .map(|snap| snap.unwrap().particles.body)
+        .collect();
+    assert_eq!(thinned_bodies, bodies);
+
+    safely_remove_file_if_exists(&rusty_fname);
+    safely_remove_file_if_exists(&thinned_fname);
+}
+
+#[test]
+fn hoomd_append_mode() {
+    let rusty_fname = get_test_file_name("hoomd_append_mode");
+
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 2;
+    snap.particles.typeid = Some(vec![0, 1]);
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file.append(&snap).unwrap();
+        hoomd_file.append(&snap).unwrap();
+    }
+
+    {
+        let gsd_file = fl::open!(&rusty_fname, "ab").unwrap();
+        let mut hoomd_file = hoomd::HOOMDTrajectory::try_new(gsd_file).unwrap();
+        hoomd_file.append(&snap).unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(gsd_file.nframes(), 3);
+    // the typeids match frame 0, so the appended frame inherits them
+    assert!(!gsd_file.chunk_exists(2, "particles/typeid"));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_frames_with_chunk() {
+    let rusty_fname = get_test_file_name("hoomd_frames_with_chunk");
+
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        for frame in 0..5u64 {
+            gsd_file
+                .write_chunk("configuration/step", &vec![frame])
+                .unwrap();
+            if frame % 2 == 1 {
+                gsd_file
+                    .write_chunk("log/energy", &vec![frame as f64])
+                    .unwrap();
+            }
+            gsd_file.end_frame().unwrap();
+        }
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let frames: Vec<(usize, ndarray::Array2<f64>)> = hoomd_file
+        .frames_with_chunk("log/energy")
+        .collect::<Result<_, GsdError>>()
+        .unwrap();
+    assert_eq!(
+        frames,
+        vec![(1, ndarray::arr2(&[[1.0]])), (3, ndarray::arr2(&[[3.0]]))]
+    );
+    assert!(matches!(
+        hoomd_file.frames_with_chunk::<f32>("log/energy").next(),
+        Some(Err(GsdError::TypeMismatch { .. }))
+    ));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_read_bonds() {
+    let rusty_fname = get_test_file_name("hoomd_read_bonds");
+
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 3;
+    snap.bonds.n = 2;
+    snap.bonds.types = vec!["A-A".to_owned(), "A-B".to_owned()];
+    snap.bonds.typeid = vec![0, 1];
+    snap.bonds.group = ndarray::arr2(&[[0u32, 1], [1, 2]]);
+    snap.angles.n = 1;
+    snap.angles.typeid = vec![0];
+    snap.angles.group = ndarray::arr2(&[[0u32, 1, 2]]);
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file.append(&snap).unwrap();
+        hoomd_file.append(&snap).unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    for read in hoomd_file.try_iter() {
+        let read = read.unwrap();
+        assert_eq!(read.bonds, snap.bonds);
+        assert_eq!(read.angles, snap.angles);
+        assert_eq!(read.dihedrals.n, 0);
+        assert!(read.dihedrals.group.is_empty());
+    }
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+/// Write a hoomd file with `nframes` frames, storing each frame's index as its step.
+fn write_steps(rusty_fname: &str, nframes: u64) {
+    let mut gsd_file = fl::open!(rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+    for frame in 0..nframes {
+        gsd_file
+            .write_chunk("configuration/step", &vec![frame])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+}
+
+fn steps(snaps: impl Iterator<Item = hoomd::OwnedSnapshot>) -> Vec<u64> {
+    snaps.map(|snap| snap.configuration.step()).collect()
+}
+
+#[test]
+fn hoomd_iterate_backwards() {
+    let rusty_fname = get_test_file_name("hoomd_iterate_backwards");
+    write_steps(&rusty_fname, 6);
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    assert_eq!(steps(hoomd_file.into_iter().rev()), vec![5, 4, 3, 2, 1, 0]);
+
+    // strides that do not divide the range length end on the last frame they reach
+    let view = hoomd_file.view((0..6, 4));
+    assert_eq!(steps(view.into_iter().rev()), vec![4, 0]);
+    let view = hoomd_file.view((1..6, 2));
+    assert_eq!(steps(view.into_iter().rev()), vec![5, 3, 1]);
+
+    // alternating ends meet in the middle without repeats
+    let view = hoomd_file.view((0..6, 2));
+    let mut iter = view.into_iter();
+    let front = iter.next().unwrap().configuration.step();
+    let back = iter.next_back().unwrap().configuration.step();
+    let middle = iter.next().unwrap().configuration.step();
+    assert_eq!((front, back, middle), (0, 4, 2));
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_iterator_len() {
+    let rusty_fname = get_test_file_name("hoomd_iterator_len");
+    write_steps(&rusty_fname, 5);
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let view = hoomd_file.view((0..5, 2));
+    let mut iter = view.into_iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    iter.next();
+    assert_eq!(iter.len(), 2);
+
+    let snaps: Vec<hoomd::OwnedSnapshot> = view.into_iter().collect();
+    assert_eq!(snaps.len(), view.into_iter().len());
+    assert_eq!(steps(view.into_iter()), vec![0, 2, 4]);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_frame_batches_memlimit() {
+    let rusty_fname = get_test_file_name("hoomd_frame_batches_memlimit");
+    write_steps(&rusty_fname, 7);
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+
+    let batch_steps = |max_bytes| -> Vec<Vec<u64>> {
+        hoomd_file
+            .frame_batches_memlimit(max_bytes)
+            .map(|batch| steps(batch.unwrap().into_iter()))
+            .collect()
+    };
+    // each frame stores a single u64 step, so 8 bytes per frame
+    assert_eq!(batch_steps(24), vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+    assert_eq!(batch_steps(31), batch_steps(24));
+    assert_eq!(batch_steps(56), vec![vec![0, 1, 2, 3, 4, 5, 6]]);
+    // limits below one frame still make progress, one frame at a time
+    assert_eq!(batch_steps(0).len(), 7);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_random_access() {
+    let rusty_fname = get_test_file_name("hoomd_random_access");
+    write_steps(&rusty_fname, 3);
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    assert_eq!(hoomd_file.len(), 3);
+    assert!(!hoomd_file.is_empty());
+    assert_eq!(hoomd_file.index(2).configuration.step(), 2);
+    assert_eq!(
+        hoomd_file.get(1).map(|snap| snap.configuration.step()),
+        Some(1)
+    );
+    assert!(hoomd_file.get(3).is_none());
+    assert_eq!(steps(hoomd_file.iter()), vec![0, 1, 2]);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_frame_at_step() {
+    let rusty_fname = get_test_file_name("hoomd_frame_at_step");
+
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        for step in [10u64, 20, 50, 51, 1000] {
+            gsd_file
+                .write_chunk("configuration/step", &vec![step])
+                .unwrap();
+            gsd_file.end_frame().unwrap();
+        }
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    assert_eq!(hoomd_file.frame_at_step(10), Ok(Some(0)));
+    assert_eq!(hoomd_file.frame_at_step(51), Ok(Some(3)));
+    assert_eq!(hoomd_file.frame_at_step(1000), Ok(Some(4)));
+    assert_eq!(hoomd_file.frame_at_step(30), Ok(None));
+    assert_eq!(hoomd_file.frame_at_step(5), Ok(None));
+
+    assert_eq!(hoomd_file.frame_at_or_before_step(5), Ok(None));
+    assert_eq!(hoomd_file.frame_at_or_before_step(20), Ok(Some(1)));
+    assert_eq!(hoomd_file.frame_at_or_before_step(999), Ok(Some(3)));
+    assert_eq!(hoomd_file.frame_at_or_before_step(u64::MAX), Ok(Some(4)));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_frame_writer() {
+    let rusty_fname = get_test_file_name("fl_frame_writer");
+
+    let mut gsd_file =
+        fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
+
+    {
+        let mut frame = gsd_file.frame();
+        frame.write_chunk("chunk1", &vec![1.0f32, 2.0]).unwrap();
+    }
+    assert_eq!(gsd_file.nframes(), 1);
+
+    let mut frame = gsd_file.frame();
+    frame.write_chunk("chunk1", &vec![3.0f32]).unwrap();
+    frame.commit().unwrap();
+    assert_eq!(gsd_file.nframes(), 2);
+
+    assert_eq!(
+        gsd_file.read_chunk_flat::<f32>(1, "chunk1").unwrap(),
+        ndarray::Array1::from(vec![3.0f32])
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_write_frame() {
+    let rusty_fname = get_test_file_name("fl_write_frame");
+
+    let mut gsd_file =
+        fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
+
+    let position = ndarray::Array2::<f32>::from_elem((2, 3), 1.5);
+    let typeid = ndarray::Array1::from(vec![0u32, 1]);
+    gsd_file
+        .write_frame(&[
+            ("particles/position", position.view().into()),
+            ("particles/typeid", typeid.view().into()),
+        ])
+        .unwrap();
+    assert_eq!(gsd_file.nframes(), 1);
+    assert_eq!(
+        gsd_file.read_chunk::<f32>(0, "particles/position").unwrap(),
+        position
+    );
+    assert_eq!(
+        gsd_file
+            .read_chunk_flat::<u32>(0, "particles/typeid")
+            .unwrap(),
+        typeid
+    );
+
+    assert!(matches!(
+        gsd_file.write_frame(&[
+            ("particles/typeid", typeid.view().into()),
+            ("particles/typeid", typeid.view().into()),
+        ]),
+        Err(GsdError::InvalidArgument(_))
+    ));
+    assert_eq!(gsd_file.nframes(), 1);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_max_write_buffer_size() {
+    let rusty_fname = get_test_file_name("fl_max_write_buffer_size");
+
+    let mut gsd_file =
+        fl::open!(&rusty_fname, "wb+", "My application", "My Schema", (1, 0)).unwrap();
+
+    let size = 2 * gsd_file.max_write_buffer_size() + 1024;
+    gsd_file.set_max_write_buffer_size(size).unwrap();
+    assert_eq!(gsd_file.max_write_buffer_size(), size);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_builder() {
+    let rusty_fname = get_test_file_name("fl_builder");
+
+    let missing = fl::GSDFile::builder()
+        .mode(fl::Mode::Write)
+        .application("My application")
+        .open(&rusty_fname);
+    match missing {
+        Err(GsdError::InvalidArgument(message)) => {
+            assert!(message.contains("schema, schema_version"), "{}", message)
+        }
+        _ => panic!("expected missing metadata to be rejected"),
+    }
+
+    {
+        let mut gsd_file = fl::GSDFileBuilder::new()
+            .mode(fl::Mode::Write)
+            .application("My application")
+            .schema("My Schema")
+            .schema_version(1, 2)
+            .open(&rusty_fname)
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let gsd_file = fl::GSDFile::builder().open(&rusty_fname).unwrap();
+    assert_eq!(gsd_file.mode(), "rb");
+    assert_eq!(gsd_file.application(), "My application");
+    assert_eq!(gsd_file.schema(), "My Schema");
+    assert_eq!(gsd_file.schema_version(), (1, 2));
+    assert_eq!(gsd_file.nframes(), 1);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_snapshot_validate() {
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 2;
+    snap.particles.position = Some(ndarray::Array2::zeros((2, 3)));
+    snap.particles.mass = Some(vec![1.0, 2.0]);
+    snap.bonds.n = 1;
+    snap.bonds.group = ndarray::arr2(&[[0u32, 1]]);
+    assert_eq!(snap.validate(), Ok(()));
+
+    let mut bad = snap.clone();
+    bad.particles.mass = Some(vec![1.0]);
+    bad.particles.velocity = Some(ndarray::Array2::zeros((3, 3)));
+    match bad.validate() {
+        Err(GsdError::InvalidArgument(message)) => assert!(message.contains("particles/mass")),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let mut bad = snap.clone();
+    bad.bonds.group = ndarray::arr2(&[[0u32, 1, 2]]);
+    match bad.validate() {
+        Err(GsdError::InvalidArgument(message)) => assert!(message.contains("bonds/group")),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let rusty_fname = get_test_file_name("hoomd_snapshot_validate");
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        assert!(hoomd_file.append(&bad).is_err());
+    }
+    assert_eq!(fl::open!(&rusty_fname, "rb").unwrap().nframes(), 0);
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_box_matrix() {
+    let configuration = hoomd::ConfigurationData::new(0, 3, [2.0, 4.0, 5.0, 0.5, -0.25, 1.0]);
+    assert_eq!(
+        configuration.box_matrix(),
+        [[2.0, 2.0, -1.25], [0.0, 4.0, 5.0], [0.0, 0.0, 5.0]]
+    );
+    // tilt does not change the volume
+    assert_eq!(configuration.box_volume(), 40.0);
+
+    let configuration = hoomd::ConfigurationData::new(0, 2, [2.0, 4.0, 0.0, 0.5, 0.0, 0.0]);
+    assert_eq!(configuration.box_volume(), 8.0);
+}
+
+#[test]
+fn hoomd_wrap_positions() {
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.configuration = hoomd::ConfigurationData::new(0, 3, [10.0, 10.0, 10.0, 0.5, 0.0, 0.0]);
+    snap.particles.n = 2;
+    // the second particle is two box vectors a1 and -1 box vector a3 away from (1, 2, 3)
+    snap.particles.position = Some(ndarray::arr2(&[[1.0f32, 2.0, 3.0], [21.0, 2.0, -7.0]]));
+    snap.particles.image = Some(ndarray::arr2(&[[0, 0, 0], [-2, 0, 1]]));
+
+    let wrapped = snap.wrap_positions();
+    for r in wrapped.outer_iter() {
+        assert!((r[0] - 1.0).abs() < 1e-5 && (r[1] - 2.0).abs() < 1e-5);
+        assert!((r[2] - 3.0).abs() < 1e-5);
+    }
+
+    // tilted box: y beyond ly / 2 shifts x by xy * ly
+    snap.particles.position = Some(ndarray::arr2(&[[0.0f32, 6.0, 0.0], [0.0, 0.0, 0.0]]));
+    let wrapped = snap.wrap_positions();
+    assert!((wrapped[[0, 0]] + 5.0).abs() < 1e-5);
+    assert!((wrapped[[0, 1]] + 4.0).abs() < 1e-5);
+
+    // 2D systems keep their z coordinate
+    snap.configuration = hoomd::ConfigurationData::new(0, 2, [10.0, 10.0, 0.0, 0.0, 0.0, 0.0]);
+    snap.particles.position = Some(ndarray::arr2(&[[12.0f32, -7.0, 0.25], [0.0, 0.0, 0.0]]));
+    let wrapped = snap.wrap_positions();
+    assert!((wrapped[[0, 0]] - 2.0).abs() < 1e-5);
+    assert!((wrapped[[0, 1]] - 3.0).abs() < 1e-5);
+    assert_eq!(wrapped[[0, 2]], 0.25);
+}
+
+#[test]
+fn hoomd_snapshot_outlives_trajectory() {
+    let rusty_fname = get_test_file_name("hoomd_snapshot_outlives_trajectory");
+
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 2;
+    snap.particles.types = Some(vec!["A".to_owned()]);
+    snap.constraints.n = 1;
+    snap.constraints.value = vec![1.5];
+    snap.constraints.group = ndarray::arr2(&[[0u32, 1]]);
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file.append(&snap).unwrap();
+    }
+
+    let read = {
+        let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+        hoomd_file.index(0)
+    };
+    assert_eq!(read, snap);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn hoomd_snapshot_serde_round_trip() {
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.configuration = hoomd::ConfigurationData::new(7, 3, [4.0, 5.0, 6.0, 0.0, 0.0, 0.0]);
+    snap.particles.n = 2;
+    snap.particles.position = Some(ndarray::arr2(&[[0.0f32, 1.0, 2.0], [3.0, 4.0, 5.0]]));
+    snap.particles.types = Some(vec!["A".to_owned()]);
+    snap.bonds.n = 1;
+    snap.bonds.types = vec!["bond".to_owned()];
+    snap.bonds.typeid = vec![0];
+    snap.bonds.group = ndarray::arr2(&[[0u32, 1]]);
+
+    let json = serde_json::to_string(&snap).unwrap();
+    assert!(json.contains(r#""position":[[0.0,1.0,2.0],[3.0,4.0,5.0]]"#));
+    assert!(json.contains(r#""group":[[0,1]]"#));
+    let read: hoomd::OwnedSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, snap);
+
+    // ragged rows are not an array
+    let ragged = json.replace("[[0,1]]", "[[0,1],[2]]");
+    assert!(serde_json::from_str::<hoomd::OwnedSnapshot>(&ragged).is_err());
+}
+
+#[test]
+fn debug_summaries() {
+    let rusty_fname = get_test_file_name("debug_summaries");
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        let mut snap = hoomd::OwnedSnapshot::default();
+        snap.particles.n = 1;
+        hoomd_file.append(&snap).unwrap();
+    }
+
+    let file = fl::open!(&rusty_fname, "rb").unwrap();
+    let debug = format!("{:?}", file);
+    assert!(debug.starts_with("GSDFile {"));
+    assert!(debug.contains(r#"mode: "rb""#));
+    assert!(debug.contains(r#"schema: "hoomd""#));
+    assert!(debug.contains("nframes: 1"));
+
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 1000;
+    snap.particles.position = Some(ndarray::Array2::zeros((1000, 3)));
+    snap.particles.mass = Some(vec![1.0; 1000]);
+    let debug = format!("{:?}", snap.particles);
+    assert_eq!(
+        debug,
+        r#"ParticleData { n: 1000, types: None, present: ["position", "mass"] }"#
+    );
+    assert!(format!("{:?}", snap).contains("BondData<3> { n: 0, types: [] }"));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn fl_read_frames_parallel() {
+    let rusty_fname = get_test_file_name("fl_read_frames_parallel");
+    write_steps(&rusty_fname, 64);
+    let path = std::path::Path::new(&rusty_fname);
+
+    let read_step = |file: &mut fl::GSDFile, frame: usize| {
+        file.read_chunk_flat::<u64>(frame, "configuration/step")
+            .unwrap()[0]
+    };
+    let parallel = fl::read_frames_parallel(path, 0..64, read_step).unwrap();
+
+    let mut file = fl::open!(&rusty_fname, "rb").unwrap();
+    let serial: Vec<u64> = (0..64).map(|frame| read_step(&mut file, frame)).collect();
+    assert_eq!(parallel, serial);
+    assert_eq!(parallel.iter().sum::<u64>(), (0..64).sum());
+
+    assert!(matches!(
+        fl::read_frames_parallel(path, 60..65, read_step),
+        Err(GsdError::FrameOutOfRange {
+            frame: 64,
+            nframes: 64
+        })
+    ));
+    // empty ranges read nothing, even when they start at or past the end
+    assert_eq!(
+        fl::read_frames_parallel(path, 64..64, read_step),
+        Ok(vec![])
+    );
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = 70..65;
+    assert_eq!(
+        fl::read_frames_parallel(path, reversed, read_step),
+        Ok(vec![])
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn fl_mmap_read_chunk_view() {
+    let rusty_fname = get_test_file_name("fl_mmap_read_chunk_view");
+    let position = ndarray::Array2::from_shape_fn((5, 3), |(i, j)| (3 * i + j) as f32);
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        for frame in 0..3u8 {
+            // an odd-sized chunk ahead of the positions leaves some of them unaligned
+            gsd_file.write_chunk("odd", &vec![frame]).unwrap();
+            gsd_file
+                .write_chunk("particles/position", &(&position + frame as f32))
+                .unwrap();
+            gsd_file.end_frame().unwrap();
+        }
+    }
+
+    let mapped = fl::GSDFile::open_mmap(&rusty_fname).unwrap();
+    assert_eq!(mapped.file().nframes(), 3);
+    for frame in 0..3 {
+        let view = mapped
+            .read_chunk_view::<f32>(frame, "particles/position")
+            .unwrap();
+        assert_eq!(view, &position + frame as f32);
+        assert_eq!(
+            mapped.read_chunk_view::<u8>(frame, "odd").unwrap(),
+            ndarray::arr2(&[[frame as u8]])
+        );
+    }
+    assert!(matches!(
+        mapped.read_chunk_view::<f64>(0, "particles/position"),
+        Err(GsdError::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        mapped.read_chunk_view::<f32>(0, "missing"),
+        Err(GsdError::ChunkNotFound { .. })
+    ));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_cached_lookups() {
+    let rusty_fname = get_test_file_name("fl_cached_lookups");
+    write_steps(&rusty_fname, 1000);
+
+    let mut gsd_file = fl::open!(&rusty_fname, "rb+").unwrap();
+    for _ in 0..2 {
+        // the second pass is served from the cache
+        for frame in 0..1000 {
+            let step = gsd_file
+                .read_chunk_flat::<u64>(frame, "configuration/step")
+                .unwrap();
+            assert_eq!(step[0], frame as u64);
+        }
+    }
+    assert!(!gsd_file.chunk_exists(1000, "configuration/step"));
+
+    // new frames are visible once committed, despite the cached miss
+    gsd_file
+        .write_chunk("configuration/step", &vec![1000u64])
+        .unwrap();
+    gsd_file.end_frame().unwrap();
+    assert!(gsd_file.chunk_exists(1000, "configuration/step"));
+
+    gsd_file.clear_cache();
+    assert_eq!(
+        gsd_file
+            .read_chunk_flat::<u64>(1000, "configuration/step")
+            .unwrap()[0],
+        1000
+    );
+
+    gsd_file.truncate().unwrap();
+    assert!(!gsd_file.chunk_exists(0, "configuration/step"));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_write_chunk_csv() {
+    let rusty_fname = get_test_file_name("fl_write_chunk_csv");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        let data = ndarray::arr2(&[[1.0f32, -0.5], [2.25, 3.0], [1e-7, 4.0]]);
+        gsd_file.write_chunk("data", &data).unwrap();
+        gsd_file.write_chunk("ids", &vec![3u32, 1, 2]).unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    let mut csv = Vec::new();
+    gsd_file.write_chunk_csv(0, "data", &mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "1.0,-0.5\n2.25,3.0\n1e-7,4.0\n"
+    );
+
+    let mut csv = Vec::new();
+    gsd_file.write_chunk_csv(0, "ids", &mut csv).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(), "3\n1\n2\n");
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_concat_into() {
+    let first_fname = get_test_file_name("hoomd_concat_into_first");
+    let second_fname = get_test_file_name("hoomd_concat_into_second");
+    let other_n_fname = get_test_file_name("hoomd_concat_into_other_n");
+    let output_fname = get_test_file_name("hoomd_concat_into_output");
+
+    let write = |fname: &str, steps: std::ops::Range<u64>, typeid: Vec<u32>| {
+        let mut hoomd_file = hoomd_open!(fname, "wb");
+        for step in steps {
+            let mut snap = hoomd::OwnedSnapshot::default();
+            snap.configuration = hoomd::ConfigurationData::new(step, 3, [1.0; 6]);
+            snap.particles.n = typeid.len() as u32;
+            snap.particles.typeid = Some(typeid.clone());
+            hoomd_file.append(&snap).unwrap();
+        }
+    };
+    write(&first_fname, 0..3, vec![0, 0]);
+    write(&second_fname, 3..5, vec![1, 1]);
+    write(&other_n_fname, 5..6, vec![0, 0, 0]);
+
+    let path = std::path::Path::new;
+    hoomd::HOOMDTrajectory::concat_into(
+        &[path(&first_fname), path(&second_fname)],
+        path(&output_fname),
+        false,
+    )
+    .unwrap();
+    let output = hoomd::HOOMDTrajectory::new(fl::open!(&output_fname, "rb").unwrap());
+    assert_eq!(steps(output.iter()), vec![0, 1, 2, 3, 4]);
+    let typeids: Vec<_> = output
+        .iter()
+        .map(|snap| snap.particles.typeid.unwrap())
+        .collect();
+    assert_eq!(typeids[2], vec![0, 0]);
+    // frames of the second input keep its own frame 0 defaults
+    assert_eq!(typeids[4], vec![1, 1]);
+
+    let overlapping = [path(&first_fname), path(&first_fname)];
+    assert!(matches!(
+        hoomd::HOOMDTrajectory::concat_into(&overlapping, path(&output_fname), false),
+        Err(GsdError::InvalidArgument(_))
+    ));
+    hoomd::HOOMDTrajectory::concat_into(&overlapping, path(&output_fname), true).unwrap();
+
+    assert!(matches!(
+        hoomd::HOOMDTrajectory::concat_into(
+            &[path(&first_fname), path(&other_n_fname)],
+            path(&output_fname),
+            false
+        ),
+        Err(GsdError::InvalidArgument(_))
+    ));
+
+    for fname in [first_fname, second_fname, other_n_fname, output_fname] {
+        safely_remove_file_if_exists(&fname);
+    }
+}
+
+#[test]
+fn hoomd_concat_into_defaults() {
+    let first_fname = get_test_file_name("hoomd_concat_into_defaults_first");
+    let second_fname = get_test_file_name("hoomd_concat_into_defaults_second");
+    let output_fname = get_test_file_name("hoomd_concat_into_defaults_output");
+
+    // the second input stores no box, masses or types, so HOOMD reads its defaults
+    {
+        let mut gsd_file = fl::open!(&second_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        for step in 2..4u64 {
+            gsd_file
+                .write_chunk("configuration/step", &vec![step])
+                .unwrap();
+            gsd_file.write_chunk("particles/N", &vec![2u32]).unwrap();
+            gsd_file.end_frame().unwrap();
+        }
+    }
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 2;
+    snap.particles.mass = Some(vec![2.0, 3.0]);
+    snap.particles.types = Some(vec!["big".to_owned()]);
+    {
+        let mut hoomd_file = hoomd_open!(&first_fname, "wb");
+        for step in 0..2 {
+            snap.configuration = hoomd::ConfigurationData::new(step, 3, [5.0; 6]);
+            hoomd_file.append(&snap).unwrap();
+        }
+    }
+
+    let path = std::path::Path::new;
+    hoomd::HOOMDTrajectory::concat_into(
+        &[path(&first_fname), path(&second_fname)],
+        path(&output_fname),
+        false,
+    )
+    .unwrap();
+    let output = hoomd::HOOMDTrajectory::new(fl::open!(&output_fname, "rb").unwrap());
+    assert_eq!(steps(output.iter()), vec![0, 1, 2, 3]);
+    for frame in 0..2 {
+        let snap = output.index(frame);
+        assert_eq!(snap.configuration.box_(), [5.0; 6]);
+        assert_eq!(snap.particles.mass, Some(vec![2.0, 3.0]));
+        assert_eq!(snap.particles.types, Some(vec!["big".to_owned()]));
+    }
+    // frames of the second input store the HOOMD defaults instead of inheriting the first's
+    for frame in 2..4 {
+        let snap = output.index(frame);
+        assert_eq!(snap.configuration.box_(), [1.0, 1.0, 1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(snap.particles.mass, Some(vec![1.0, 1.0]));
+        assert_eq!(snap.particles.types, Some(vec!["A".to_owned()]));
+    }
+
+    for fname in [first_fname, second_fname, output_fname] {
+        safely_remove_file_if_exists(&fname);
+    }
+}
+
+#[test]
+fn hoomd_write_thinned() {
+    let rusty_fname = get_test_file_name("hoomd_write_thinned");
+    let thinned_fname = get_test_file_name("hoomd_write_thinned_output");
+    write_steps(&rusty_fname, 10);
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    hoomd_file.write_thinned(&thinned_fname, 3).unwrap();
+    assert!(matches!(
+        hoomd_file.write_thinned(&thinned_fname, 0),
+        Err(GsdError::InvalidArgument(_))
+    ));
+
+    let thinned = hoomd::HOOMDTrajectory::new(fl::open!(&thinned_fname, "rb").unwrap());
+    assert_eq!(thinned.len(), 4);
+    assert_eq!(steps(thinned.iter()), vec![0, 3, 6, 9]);
+
+    safely_remove_file_if_exists(&rusty_fname);
+    safely_remove_file_if_exists(&thinned_fname);
+}
+
+#[test]
+fn hoomd_nested_views() {
+    let rusty_fname = get_test_file_name("hoomd_nested_views");
+    write_steps(&rusty_fname, 20);
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+
+    let view = hoomd_file.view((2..19, 3));
+    assert_eq!(steps(view.into_iter()), vec![2, 5, 8, 11, 14, 17]);
+    assert_eq!(view.len(), 6);
+    let nested = view.view((1..10, 2));
+    assert_eq!(steps(nested.iter()), vec![5, 11, 17]);
+    assert_eq!(nested.len(), 3);
+    assert_eq!(nested.index(1).configuration.step(), 11);
+    assert_eq!(nested.at(2).unwrap().configuration.step(), 17);
+    assert!(matches!(
+        nested.at(3),
+        Err(GsdError::FrameOutOfRange {
+            frame: 3,
+            nframes: 3
+        })
+    ));
+    assert_eq!(steps(nested.view((1..3, 1)).into_iter()), vec![11, 17]);
+    assert_eq!(steps(view.view((4..100, 1)).into_iter()), vec![14, 17]);
+    // starts past the end are clamped to an empty view
+    assert_eq!(steps(view.view((7..9, 1)).into_iter()), vec![]);
+    assert!(view.view((7..9, 1)).is_empty());
+    assert_eq!(steps(hoomd_file.view((25..30, 1)).into_iter()), vec![]);
+    assert_eq!(steps(hoomd_file.view((18..30, 5)).into_iter()), vec![18]);
+    // nested steps saturate instead of overflowing
+    let sparse = view.view((0..6, usize::MAX));
+    assert_eq!(sparse.len(), 1);
+    assert_eq!(steps(sparse.view((0..1, 2)).into_iter()), vec![2]);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+#[should_panic(expected = "view step must be at least 1")]
+fn hoomd_view_rejects_zero_step() {
+    let rusty_fname = get_test_file_name("hoomd_view_rejects_zero_step");
+    write_steps(&rusty_fname, 3);
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    safely_remove_file_if_exists(&rusty_fname);
+
+    hoomd_file.view((0..3, 0));
+}
+
+#[test]
+fn hoomd_at() {
+    let rusty_fname = get_test_file_name("hoomd_at");
+    write_steps(&rusty_fname, 3);
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+
+    assert_eq!(hoomd_file.at(2).unwrap().configuration.step(), 2);
+    assert_eq!(hoomd_file.at(1).unwrap(), hoomd_file.index(1));
+    assert!(matches!(
+        hoomd_file.at(3),
+        Err(GsdError::FrameOutOfRange {
+            frame: 3,
+            nframes: 3
+        })
+    ));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_read_log() {
+    let rusty_fname = get_test_file_name("hoomd_read_log");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file
+            .write_chunk("log/md/pair/LJ/energy", &vec![-1.5f64])
+            .unwrap();
+        gsd_file
+            .write_chunk("log/particles/tag", &vec![2i32, 0, 1])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+        gsd_file
+            .write_chunk("log/md/pair/LJ/energy", &vec![-2.5f64])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let log = hoomd_file.read_log(1).unwrap();
+    assert_eq!(log.len(), 2);
+    assert_eq!(
+        log["log/md/pair/LJ/energy"],
+        hoomd::LogValue::DOUBLE(ndarray::arr2(&[[-2.5]]).into_dyn())
+    );
+    // inherited from frame 0
+    assert_eq!(
+        log["log/particles/tag"],
+        hoomd::LogValue::INT32(ndarray::arr2(&[[2], [0], [1]]).into_dyn())
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_write_log() {
+    let rusty_fname = get_test_file_name("hoomd_write_log");
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        let mut snap = hoomd::OwnedSnapshot::default();
+        snap.particles.n = 3;
+        hoomd_file
+            .write_log(
+                "md/compute/kinetic_energy",
+                hoomd::LogValue::DOUBLE(ndarray::arr0(1.25).into_dyn()),
+            )
+            .unwrap();
+        hoomd_file
+            .write_log(
+                "log/particles/tag",
+                hoomd::LogValue::UINT32(ndarray::arr1(&[2, 0, 1]).into_dyn()),
+            )
+            .unwrap();
+        hoomd_file.append(&snap).unwrap();
+
+        let too_many_dims = ndarray::Array3::<f32>::zeros((1, 1, 1)).into_dyn();
+        assert!(matches!(
+            hoomd_file.write_log("x", hoomd::LogValue::FLOAT(too_many_dims)),
+            Err(GsdError::InvalidArgument(_))
+        ));
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let log = hoomd_file.read_log(0).unwrap();
+    assert_eq!(
+        log["log/md/compute/kinetic_energy"],
+        hoomd::LogValue::DOUBLE(ndarray::arr2(&[[1.25]]).into_dyn())
+    );
+    assert_eq!(
+        log["log/particles/tag"],
+        hoomd::LogValue::UINT32(ndarray::arr2(&[[2], [0], [1]]).into_dyn())
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_snapshot_approx_eq() {
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 2;
+    snap.particles.position = Some(ndarray::arr2(&[[0.0f32, 1.0, 2.0], [3.0, 4.0, 5.0]]));
+    snap.particles.typeid = Some(vec![0, 1]);
+    snap.particles.mass = Some(vec![1.0, 2.0]);
+
+    let mut other = snap.clone();
+    assert_eq!(other, snap);
+    other.particles.position.as_mut().unwrap()[[1, 2]] += 1e-4;
+    other.particles.mass.as_mut().unwrap()[0] -= 1e-4;
+    assert_ne!(other, snap);
+    assert!(other.approx_eq(&snap, 1e-3));
+    assert!(!other.approx_eq(&snap, 1e-5));
+
+    // integer fields are compared exactly
+    let mut other = snap.clone();
+    other.particles.typeid = Some(vec![0, 0]);
+    assert!(!other.approx_eq(&snap, 1.0));
+
+    // a field present on only one side is a difference
+    let mut other = snap.clone();
+    other.particles.charge = Some(vec![0.0, 0.0]);
+    assert!(!other.approx_eq(&snap, 1.0));
+    assert!(!snap.approx_eq(&other, 1.0));
+}
+
+#[test]
+fn fl_truncate_reports_removed_frames() {
+    let rusty_fname = get_test_file_name("fl_truncate_reports_removed_frames");
+    write_steps(&rusty_fname, 5);
+
+    let mut gsd_file = fl::open!(&rusty_fname, "rb+").unwrap();
+    assert_eq!(gsd_file.nframes(), 5);
+    // truncating keeps none of the frames, frame 0 included
+    assert_eq!(gsd_file.truncate().unwrap(), 5);
+    assert_eq!(gsd_file.nframes(), 0);
+    drop(gsd_file);
+
+    assert_eq!(fl::open!(&rusty_fname, "rb").unwrap().nframes(), 0);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_mode_guards() {
+    let rusty_fname = get_test_file_name("fl_mode_guards");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        assert!(gsd_file.is_writable() && !gsd_file.is_readable());
+        gsd_file.write_chunk("data", &vec![1.0f32]).unwrap();
+        gsd_file.end_frame().unwrap();
+        assert!(matches!(
+            gsd_file.read_chunk::<f32>(0, "data"),
+            Err(GsdError::NotReadable(_))
+        ));
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "ab").unwrap();
+    assert!(matches!(
+        gsd_file.read_chunk_as::<f64>(0, "data"),
+        Err(GsdError::NotReadable(_))
+    ));
+    drop(gsd_file);
+
+    let mut gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert!(gsd_file.is_readable() && !gsd_file.is_writable());
+    match gsd_file.write_chunk("data", &vec![2.0f32]) {
+        Err(GsdError::NotWritable(message)) => assert!(message.contains(&rusty_fname)),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(matches!(
+        gsd_file.end_frame(),
+        Err(GsdError::NotWritable(_))
+    ));
+    assert!(matches!(gsd_file.truncate(), Err(GsdError::NotWritable(_))));
+    assert_eq!(gsd_file.read_chunk::<f32>(0, "data").unwrap()[[0, 0]], 1.0);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_chunks() {
+    let rusty_fname = get_test_file_name("fl_chunks");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file
+            .write_chunk("configuration/step", &vec![0u64])
+            .unwrap();
+        gsd_file
+            .write_chunk("particles/position", &ndarray::Array2::<f32>::zeros((4, 3)))
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+        gsd_file
+            .write_chunk("configuration/step", &vec![1u64])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(gsd_file.chunks().count(), 3);
+    let positions: Vec<_> = gsd_file
+        .chunks()
+        .filter(|(_, name, _)| name == "particles/position")
+        .collect();
+    assert_eq!(
+        positions,
+        vec![(
+            0,
+            "particles/position".to_owned(),
+            fl::ChunkInfo {
+                type_: GSDType::FLOAT,
+                n: 4,
+                m: 3,
+                flags: 0
+            }
+        )]
+    );
+    let frames: Vec<usize> = gsd_file.chunks().map(|(frame, _, _)| frame).collect();
+    assert_eq!(frames, vec![0, 0, 1]);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_empty_chunks() {
+    let rusty_fname = get_test_file_name("fl_empty_chunks");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file.write_chunk("empty", &Vec::<f32>::new()).unwrap();
+        gsd_file
+            .write_chunk("no_rows", &ndarray::Array2::<u32>::zeros((0, 3)))
+            .unwrap();
+        assert!(matches!(
+            gsd_file.write_chunk("no_columns", &ndarray::Array2::<u32>::zeros((2, 0))),
+            Err(GsdError::InvalidArgument(_))
+        ));
+        gsd_file.end_frame().unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(gsd_file.chunk_info(0, "empty").unwrap().n, 0);
+    assert_eq!(
+        gsd_file.read_chunk::<f32>(0, "empty").unwrap().dim(),
+        (0, 1)
+    );
+    assert!(gsd_file
+        .read_chunk_flat::<f32>(0, "empty")
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        gsd_file.read_chunk::<u32>(0, "no_rows").unwrap().dim(),
+        (0, 3)
+    );
+    assert!(!gsd_file.chunk_exists(0, "no_columns"));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_frame_out_of_range() {
+    let rusty_fname = get_test_file_name("fl_frame_out_of_range");
+    write_steps(&rusty_fname, 2);
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    let out_of_range = GsdError::FrameOutOfRange {
+        frame: 2,
+        nframes: 2,
+    };
+    assert_eq!(
+        gsd_file.read_chunk::<u64>(2, "configuration/step"),
+        Err(out_of_range.clone())
+    );
+    assert_eq!(
+        gsd_file.read_chunk_flat::<u64>(2, "configuration/step"),
+        Err(out_of_range.clone())
+    );
+    assert_eq!(
+        gsd_file.chunk_info(2, "configuration/step"),
+        Err(out_of_range)
+    );
+    // a valid frame without the chunk is still reported as a missing chunk
+    assert!(matches!(
+        gsd_file.read_chunk::<u64>(1, "configuration/box"),
+        Err(GsdError::ChunkNotFound { frame: 1, .. })
+    ));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_typed_chunk() {
+    let rusty_fname = get_test_file_name("fl_typed_chunk");
+    write_steps(&rusty_fname, 50);
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    let step = gsd_file.typed_chunk::<u64>("configuration/step").unwrap();
+    assert_eq!(step.name(), "configuration/step");
+    for frame in 0..50 {
+        assert_eq!(
+            step.read(&gsd_file, frame).unwrap(),
+            gsd_file
+                .read_chunk::<u64>(frame, "configuration/step")
+                .unwrap()
+        );
+    }
+
+    assert!(matches!(
+        gsd_file
+            .typed_chunk::<u32>("configuration/step")
+            .unwrap()
+            .read(&gsd_file, 0),
+        Err(GsdError::TypeMismatch {
+            expected: GSDType::UINT32,
+            found: GSDType::UINT64
+        })
+    ));
+    assert!(matches!(
+        gsd_file
+            .typed_chunk::<u64>("missing")
+            .unwrap()
+            .read(&gsd_file, 0),
+        Err(GsdError::ChunkNotFound { frame: 0, .. })
+    ));
+    assert!(matches!(
+        step.read(&gsd_file, 50),
+        Err(GsdError::FrameOutOfRange { .. })
+    ));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_read_chunk_bytes() {
+    let rusty_fname = get_test_file_name("fl_read_chunk_bytes");
+    let data = ndarray::arr2(&[[1.0f64, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file.write_chunk("data", &data).unwrap();
+        gsd_file.write_chunk("flags", &vec![1u8, 2, 3]).unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    let (bytes, type_, shape) = gsd_file.read_chunk_bytes(0, "data").unwrap();
+    assert_eq!((type_, shape), (GSDType::DOUBLE, (3, 2)));
+    assert_eq!(bytes.len(), 3 * 2 * 8);
+    let expected: Vec<u8> = data.iter().flat_map(|x| x.to_le_bytes()).collect();
+    assert_eq!(bytes, expected);
+
+    let (bytes, type_, shape) = gsd_file.read_chunk_bytes(0, "flags").unwrap();
+    assert_eq!(
+        (bytes, type_, shape),
+        (vec![1, 2, 3], GSDType::UINT8, (3, 1))
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_iter_rev() {
+    let rusty_fname = get_test_file_name("hoomd_iter_rev");
+    write_steps(&rusty_fname, 5);
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let steps = steps(hoomd_file.iter_rev());
+    assert_eq!(steps.len(), 5);
+    assert!(steps.windows(2).all(|pair| pair[0] > pair[1]));
+    assert_eq!(steps, vec![4, 3, 2, 1, 0]);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_validate_well_formed() {
+    let rusty_fname = get_test_file_name("fl_validate_well_formed");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        for frame in 0..4u64 {
+            gsd_file
+                .write_chunk("configuration/step", &vec![frame])
+                .unwrap();
+            gsd_file
+                .write_chunk(
+                    "particles/position",
+                    &ndarray::Array2::<f32>::zeros((10, 3)),
+                )
+                .unwrap();
+            gsd_file.write_chunk("empty", &Vec::<u8>::new()).unwrap();
+            gsd_file.end_frame().unwrap();
+        }
+        // frames still in the write buffer are within the file as well
+        assert_eq!(gsd_file.validate(), Ok(()));
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(gsd_file.validate(), Ok(()));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_view_str() {
+    let rusty_fname = get_test_file_name("hoomd_view_str");
+    write_steps(&rusty_fname, 8);
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let view_steps = |spec: &str| steps(hoomd_file.view_str(spec).unwrap().into_iter());
+
+    assert_eq!(view_steps("::2"), vec![0, 2, 4, 6]);
+    assert_eq!(view_steps("5:"), vec![5, 6, 7]);
+    assert_eq!(view_steps(":-1"), vec![0, 1, 2, 3, 4, 5, 6]);
+    assert_eq!(view_steps("-3::2"), vec![5, 7]);
+    assert_eq!(view_steps("1:100:3"), vec![1, 4, 7]);
+    assert_eq!(view_steps("6:2"), vec![]);
+
+    for spec in ["", "3", "a:b", "1:2:3:4", "::0", "::-1", "1.5:"] {
+        assert!(
+            matches!(hoomd_file.view_str(spec), Err(GsdError::InvalidArgument(_))),
+            "{}",
+            spec
+        );
+    }
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_get_signed() {
+    let rusty_fname = get_test_file_name("hoomd_get_signed");
+    write_steps(&rusty_fname, 4);
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let step = |idx: i64| {
+        hoomd_file
+            .get_signed(idx)
+            .map(|snap| snap.configuration.step())
+    };
+
+    assert_eq!(step(-1), Some(3));
+    assert_eq!(step(-4), Some(0));
+    assert_eq!(step(-5), None);
+    assert_eq!(step(1), Some(1));
+    assert_eq!(step(4), None);
+    assert_eq!(step(i64::MIN), None);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_chunk_frequency() {
+    let rusty_fname = get_test_file_name("fl_chunk_frequency");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        for frame in 0..5u64 {
+            gsd_file
+                .write_chunk("configuration/step", &vec![frame])
+                .unwrap();
+            if frame == 0 {
+                gsd_file.write_chunk("particles/N", &vec![2u32]).unwrap();
+            }
+            gsd_file.end_frame().unwrap();
+        }
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    let frequency = gsd_file.chunk_frequency();
+    assert_eq!(frequency.len(), 2);
+    assert_eq!(frequency["configuration/step"], 5);
+    assert_eq!(frequency["particles/N"], 1);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_copy_chunk() {
+    let src_fname = get_test_file_name("fl_copy_chunk_src");
+    let dst_fname = get_test_file_name("fl_copy_chunk_dst");
+    let data = ndarray::arr2(&[[1.5f64, -2.0, 3.25], [4.0, 5.5, -6.75]]);
+    {
+        let mut gsd_file = fl::open!(&src_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file.end_frame().unwrap();
+        gsd_file.write_chunk("log/value", &data).unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let src = fl::open!(&src_fname, "rb").unwrap();
+    {
+        let mut dst = fl::open!(&dst_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        fl::copy_chunk(&src, 1, &mut dst, "log/value").unwrap();
+        assert!(matches!(
+            fl::copy_chunk(&src, 0, &mut dst, "log/value"),
+            Err(GsdError::ChunkNotFound { frame: 0, .. })
+        ));
+        dst.end_frame().unwrap();
+    }
+
+    let dst = fl::open!(&dst_fname, "rb").unwrap();
+    assert_eq!(
+        dst.chunk_info(0, "log/value"),
+        src.chunk_info(1, "log/value")
+    );
+    assert_eq!(dst.read_chunk::<f64>(0, "log/value").unwrap(), data);
+
+    safely_remove_file_if_exists(&src_fname);
+    safely_remove_file_if_exists(&dst_fname);
+}
+
+#[test]
+fn fl_needs_upgrade() {
+    let rusty_fname = get_test_file_name("fl_needs_upgrade");
+    write_steps(&rusty_fname, 2);
+
+    let mut gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(gsd_file.gsd_version(), fl::CURRENT_GSD_VERSION);
+    assert!(!gsd_file.needs_upgrade());
+    assert!(matches!(gsd_file.upgrade(), Err(GsdError::NotWritable(_))));
+    drop(gsd_file);
+
+    let mut gsd_file = fl::open!(&rusty_fname, "rb+").unwrap();
+    assert_eq!(gsd_file.upgrade(), Ok(false));
+    assert_eq!(gsd_file.nframes(), 2);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_frame_builder() {
+    let rusty_fname = get_test_file_name("fl_frame_builder");
+    let mut gsd_file = fl::open!(&rusty_fname, "wb+", "test", "hoomd", (1, 4)).unwrap();
+
+    let position = ndarray::Array2::<f32>::from_elem((2, 3), 0.5);
+    let step = ndarray::Array1::from(vec![10u64]);
+    gsd_file
+        .begin_frame()
+        .chunk("particles/position", position.view())
+        .chunk("configuration/step", step.view())
+        .finish()
+        .unwrap();
+    assert_eq!(gsd_file.nframes(), 1);
+    assert_eq!(
+        gsd_file.read_chunk::<f32>(0, "particles/position").unwrap(),
+        position
+    );
+    assert_eq!(
+        gsd_file
+            .read_chunk_flat::<u64>(0, "configuration/step")
+            .unwrap(),
+        step
+    );
+
+    // a bad shape is reported before the valid chunk ahead of it is written
+    let no_columns = ndarray::Array2::<f32>::zeros((2, 0));
+    assert!(matches!(
+        gsd_file
+            .begin_frame()
+            .chunk("configuration/step", step.view())
+            .chunk("particles/position", no_columns.view())
+            .finish(),
+        Err(GsdError::InvalidArgument(_))
+    ));
+    gsd_file.end_frame().unwrap();
+    assert!(!gsd_file.chunk_exists(1, "configuration/step"));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_snapshot_clone_is_deep() {
+    let rusty_fname = get_test_file_name("hoomd_snapshot_clone_is_deep");
+    let copy_fname = get_test_file_name("hoomd_snapshot_clone_is_deep_copy");
+
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 2;
+    snap.particles.position = Some(ndarray::arr2(&[[0.0f32, 0.0, 0.0], [1.0, 1.0, 1.0]]));
+    snap.particles.types = Some(vec!["A".to_owned()]);
+    snap.bonds.n = 1;
+    snap.bonds.types = vec!["bond".to_owned()];
+    snap.bonds.typeid = vec![0];
+    snap.bonds.group = ndarray::arr2(&[[0u32, 1]]);
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file.append(&snap).unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let original = hoomd_file.index(0);
+    let mut tweaked = original.clone();
+    tweaked.particles.position.as_mut().unwrap()[[1, 0]] = 5.0;
+    tweaked.bonds.types[0].push('B');
+    tweaked.bonds.group[[0, 1]] = 0;
+    assert_eq!(original, snap);
+    assert_ne!(tweaked, original);
+
+    {
+        let mut copy = hoomd_open!(&copy_fname, "wb");
+        copy.append(&tweaked).unwrap();
+    }
+    let copy = hoomd::HOOMDTrajectory::new(fl::open!(&copy_fname, "rb").unwrap());
+    assert_eq!(copy.index(0).particles.position.unwrap()[[1, 0]], 5.0);
+    assert_eq!(hoomd_file.index(0), snap);
+
+    safely_remove_file_if_exists(&rusty_fname);
+    safely_remove_file_if_exists(&copy_fname);
+}
+
+#[test]
+fn hoomd_particle_type_names() {
+    let rusty_fname = get_test_file_name("hoomd_particle_type_names");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file.write_chunk("particles/N", &vec![3u32]).unwrap();
+        gsd_file
+            .write_string_chunk("particles/types", &["A", "B"])
+            .unwrap();
+        gsd_file
+            .write_chunk("particles/typeid", &vec![1u32, 0, 1])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    let mut particles = hoomd_file.index(0).particles;
+    assert_eq!(particles.type_name(0), Some("B"));
+    assert_eq!(particles.type_name(1), Some("A"));
+    assert_eq!(particles.type_name(3), None);
+    let counts = particles.type_counts();
+    assert_eq!(counts.len(), 2);
+    assert_eq!((counts["A"], counts["B"]), (1, 2));
+
+    // unknown typeids and missing types do not resolve
+    particles.typeid = Some(vec![1, 0, 2]);
+    assert_eq!(particles.type_name(2), None);
+    assert_eq!(particles.type_counts()["B"], 1);
+    particles.types = None;
+    assert_eq!(particles.type_name(0), None);
+    assert!(particles.type_counts().is_empty());
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_snapshot_select() {
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 4;
+    snap.particles.position = Some(ndarray::arr2(&[
+        [0.0f32, 0.0, 0.0],
+        [1.0, 1.0, 1.0],
+        [2.0, 2.0, 2.0],
+        [3.0, 3.0, 3.0],
+    ]));
+    snap.particles.typeid = Some(vec![0, 1, 0, 1]);
+    snap.particles.types = Some(vec!["A".to_owned(), "B".to_owned()]);
+    snap.particles.mass = Some(vec![1.0, 2.0, 3.0, 4.0]);
+    snap.bonds.n = 3;
+    snap.bonds.types = vec!["a".to_owned(), "b".to_owned()];
+    snap.bonds.typeid = vec![0, 1, 1];
+    snap.bonds.group = ndarray::arr2(&[[0u32, 1], [1, 3], [3, 2]]);
+
+    let selected = snap.select(&[1, 3]);
+    assert_eq!(selected.particles.n, 2);
+    assert_eq!(
+        selected.particles.position,
+        Some(ndarray::arr2(&[[1.0f32, 1.0, 1.0], [3.0, 3.0, 3.0]]))
+    );
+    assert_eq!(selected.particles.typeid, Some(vec![1, 1]));
+    assert_eq!(selected.particles.mass, Some(vec![2.0, 4.0]));
+    assert_eq!(selected.particles.types, snap.particles.types);
+    // only the bond between particles 1 and 3 remains, renumbered to 0 and 1
+    assert_eq!(selected.bonds.n, 1);
+    assert_eq!(selected.bonds.typeid, vec![1]);
+    assert_eq!(selected.bonds.group, ndarray::arr2(&[[0u32, 1]]));
+    assert_eq!(selected.validate(), Ok(()));
+    // the original is untouched
+    assert_eq!(snap.bonds.n, 3);
+}
+
+#[test]
+fn progress_callbacks() {
+    let rusty_fname = get_test_file_name("progress_callbacks");
+    let output_fname = get_test_file_name("progress_callbacks_output");
+    write_steps(&rusty_fname, 10);
+
+    let mut calls = Vec::new();
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    gsd_file
+        .validate_with_progress(|processed, total| calls.push((processed, total)))
+        .unwrap();
+    assert_eq!(calls, (1..=10).map(|i| (i, 10)).collect::<Vec<_>>());
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(gsd_file);
+    let mut calls = Vec::new();
+    hoomd_file
+        .write_thinned_with_progress(&output_fname, 3, |processed, total| {
+            calls.push((processed, total))
+        })
+        .unwrap();
+    assert_eq!(calls, vec![(1, 4), (2, 4), (3, 4), (4, 4)]);
+
+    let path = std::path::Path::new;
+    let mut count = 0;
+    hoomd::HOOMDTrajectory::concat_into_with_progress(
+        &[path(&rusty_fname), path(&rusty_fname)],
+        path(&output_fname),
+        true,
+        |processed, total| {
+            count += 1;
+            assert_eq!(processed, count);
+            assert_eq!(total, 20);
+        },
+    )
+    .unwrap();
+    assert_eq!(count, 20);
+
+    safely_remove_file_if_exists(&rusty_fname);
+    safely_remove_file_if_exists(&output_fname);
+}
+
+#[test]
+fn fl_unsupported_element_types() {
+    let rusty_fname = get_test_file_name("fl_unsupported_element_types");
+    let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+    // GSD has no slot for 128 bit integers (or half precision floats)
+    assert!(matches!(
+        gsd_file.write_chunk("wide", &vec![1u128, 2]),
+        Err(GsdError::UnsupportedType { .. })
+    ));
+    assert_eq!(
+        gsd_file.write_chunk("size", &vec![1usize, 2]),
+        Err(GsdError::UnsupportedType {
+            type_name: "usize".to_owned()
+        })
+    );
+    gsd_file.write_chunk("narrow", &vec![1u64, 2]).unwrap();
+    gsd_file.end_frame().unwrap();
+    drop(gsd_file);
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert!(matches!(
+        gsd_file.read_chunk::<i128>(0, "narrow"),
+        Err(GsdError::UnsupportedType { .. })
+    ));
+    assert!(matches!(
+        gsd_file.typed_chunk::<u128>("narrow"),
+        Err(GsdError::UnsupportedType { .. })
+    ));
+    assert!(!gsd_file.chunk_exists(0, "wide"));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_read_chunk_scalar() {
+    let rusty_fname = get_test_file_name("fl_read_chunk_scalar");
+    let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+    gsd_file
+        .write_chunk("configuration/step", &vec![1234u64])
+        .unwrap();
+    gsd_file
+        .write_chunk("configuration/box", &vec![1.0f32; 6])
+        .unwrap();
+    gsd_file.end_frame().unwrap();
+    drop(gsd_file);
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(
+        gsd_file
+            .read_chunk_scalar::<u64>(0, "configuration/step")
+            .unwrap(),
+        1234
+    );
+    assert!(matches!(
+        gsd_file.read_chunk_scalar::<f32>(0, "configuration/box"),
+        Err(GsdError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        gsd_file.read_chunk_scalar::<u32>(0, "configuration/step"),
+        Err(GsdError::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        gsd_file.read_chunk_scalar::<u64>(0, "missing"),
+        Err(GsdError::ChunkNotFound { .. })
+    ));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_swap_element_bytes() {
+    let mut bytes: Vec<u8> = [1u16, 0x0203]
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect();
+    fl::swap_element_bytes(&mut bytes, 2);
+    let expected: Vec<u8> = [1u16, 0x0203]
+        .iter()
+        .flat_map(|x| x.to_be_bytes())
+        .collect();
+    assert_eq!(bytes, expected);
+
+    let mut bytes: Vec<u8> = [-7i32, 1 << 20]
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect();
+    fl::swap_element_bytes(&mut bytes, 4);
+    let expected: Vec<u8> = [-7i32, 1 << 20]
+        .iter()
+        .flat_map(|x| x.to_be_bytes())
+        .collect();
+    assert_eq!(bytes, expected);
+
+    let mut bytes: Vec<u8> = [1.5f32, -0.25]
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect();
+    fl::swap_element_bytes(&mut bytes, 4);
+    let swapped: Vec<f32> = bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_be_bytes(b.try_into().unwrap()))
+        .collect();
+    assert_eq!(swapped, vec![1.5, -0.25]);
+
+    let mut bytes: Vec<u8> = [std::f64::consts::PI]
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect();
+    fl::swap_element_bytes(&mut bytes, 8);
+    assert_eq!(
+        f64::from_be_bytes(bytes.try_into().unwrap()),
+        std::f64::consts::PI
+    );
+
+    // single bytes have no order to swap
+    let mut bytes = vec![1u8, 2, 3];
+    fl::swap_element_bytes(&mut bytes, 1);
+    assert_eq!(bytes, vec![1, 2, 3]);
+}
+
+#[test]
+fn hoomd_constraints_round_trip() {
+    let rusty_fname = get_test_file_name("hoomd_constraints_round_trip");
+
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 4;
+    snap.constraints = hoomd::ConstraintData::new(&[([0, 1], 1.5), ([2, 3], 0.75)]);
+    assert_eq!(snap.constraints.n, 2);
+    assert_eq!(snap.constraints.value, vec![1.5, 0.75]);
+    assert_eq!(snap.constraints.group, ndarray::arr2(&[[0u32, 1], [2, 3]]));
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file.append(&snap).unwrap();
+        hoomd_file.append(&snap).unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(
+        gsd_file
+            .read_chunk_scalar::<u32>(0, "constraints/N")
+            .unwrap(),
+        2
+    );
+    // unchanged constraints are inherited from frame 0
+    assert!(!gsd_file.chunk_exists(1, "constraints/group"));
+    let hoomd_file = hoomd::HOOMDTrajectory::new(gsd_file);
+    for read in hoomd_file.iter() {
+        assert_eq!(read.constraints, snap.constraints);
+    }
+    assert!(hoomd::ConstraintData::new(&[]).group.is_empty());
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_particle_dynamics_round_trip() {
+    let rusty_fname = get_test_file_name("hoomd_particle_dynamics_round_trip");
+
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 2;
+    snap.particles.velocity = Some(ndarray::arr2(&[[1.0f32, -2.0, 0.5], [0.0, 0.25, 3.0]]));
+    snap.particles.angmom = Some(ndarray::arr2(&[
+        [0.0f32, 1.0, 2.0, 3.0],
+        [0.0, -1.0, 0.0, 0.5],
+    ]));
+    snap.particles.moment_inertia = Some(ndarray::arr2(&[[1.0f32, 2.0, 3.0], [0.5, 0.5, 0.0]]));
+    snap.particles.image = Some(ndarray::arr2(&[[0, 1, -1], [4, 0, 0]]));
+    snap.particles.types = Some(vec!["A".to_owned()]);
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file.append(&snap).unwrap();
+        let mut moved = snap.clone();
+        moved.particles.velocity = Some(ndarray::arr2(&[[0.0f32; 3], [0.0; 3]]));
+        hoomd_file.append(&moved).unwrap();
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    assert_eq!(hoomd_file.index(0).particles, snap.particles);
+    // unchanged quantities are inherited from frame 0
+    let particles = hoomd_file.index(1).particles;
+    assert_eq!(
+        particles.velocity,
+        Some(ndarray::arr2(&[[0.0f32; 3], [0.0; 3]]))
+    );
+    assert_eq!(particles.angmom, snap.particles.angmom);
+    assert_eq!(particles.moment_inertia, snap.particles.moment_inertia);
+    assert_eq!(particles.image, snap.particles.image);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_image_flags_are_integers() {
+    let rusty_fname = get_test_file_name("hoomd_image_flags_are_integers");
+
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 1;
+    snap.particles.image = Some(ndarray::arr2(&[[1, 0, -2]]));
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file.append(&snap).unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(
+        gsd_file.chunk_info(0, "particles/image").unwrap().type_,
+        GSDType::INT32
+    );
+    let hoomd_file = hoomd::HOOMDTrajectory::new(gsd_file);
+    assert_eq!(
+        hoomd_file.index(0).particles.image,
+        Some(ndarray::arr2(&[[1, 0, -2]]))
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_reserve() {
+    let rusty_fname = get_test_file_name("fl_reserve");
+    let reserved_fname = get_test_file_name("fl_reserve_reserved");
+    let data = ndarray::Array2::from_shape_fn((1000, 3), |(i, j)| (i * 3 + j) as f32);
+
+    for (fname, reserve) in [(&rusty_fname, false), (&reserved_fname, true)] {
+        let mut gsd_file = fl::open!(fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        let default_size = gsd_file.max_write_buffer_size();
+        if reserve {
+            gsd_file.reserve(12_000, 10);
+            assert!(gsd_file.max_write_buffer_size() >= 120_000);
+            // hints never shrink the buffer and are capped
+            gsd_file.reserve(1, 1);
+            assert!(gsd_file.max_write_buffer_size() >= default_size);
+            gsd_file.reserve(u64::MAX, usize::MAX);
+            assert_eq!(
+                gsd_file.max_write_buffer_size(),
+                fl::MAX_RESERVED_WRITE_BUFFER_SIZE.max(default_size)
+            );
+        }
+        for _ in 0..10 {
+            gsd_file.write_chunk("data", &data).unwrap();
+            gsd_file.end_frame().unwrap();
+        }
+    }
+
+    let plain = fl::open!(&rusty_fname, "rb").unwrap();
+    let reserved = fl::open!(&reserved_fname, "rb").unwrap();
+    assert_eq!(reserved.nframes(), 10);
+    for frame in 0..10 {
+        assert_eq!(
+            reserved.read_chunk::<f32>(frame, "data").unwrap(),
+            plain.read_chunk::<f32>(frame, "data").unwrap()
+        );
+    }
+
+    safely_remove_file_if_exists(&rusty_fname);
+    safely_remove_file_if_exists(&reserved_fname);
+}
+
+#[test]
+fn gsd_type_names() {
+    let names = [
+        "uint8", "uint16", "uint32", "uint64", "int8", "int16", "int32", "int64", "float", "double",
+    ];
+    for (raw, name) in (1u8..=10).zip(names) {
+        let type_ = GSDType::from_raw(raw).unwrap();
+        assert_eq!(type_.to_string(), name);
+        assert_eq!(name.parse::<GSDType>().unwrap(), type_);
+    }
+    assert!(matches!(
+        "UINT8".parse::<GSDType>(),
+        Err(GsdError::InvalidArgument(_))
+    ));
+    assert_eq!(
+        GsdError::TypeMismatch {
+            expected: GSDType::FLOAT,
+            found: GSDType::DOUBLE
+        }
+        .to_string(),
+        "Type mismatch: float != double"
+    );
+}
+
+#[test]
+fn fl_read_chunk_series() {
+    let rusty_fname = get_test_file_name("fl_read_chunk_series");
+    let positions =
+        ndarray::Array3::<f32>::from_shape_fn((4, 2, 3), |(i, j, k)| (i * 6 + j * 3 + k) as f32);
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        for (frame, position) in positions.outer_iter().enumerate() {
+            gsd_file
+                .write_chunk("particles/position", position)
+                .unwrap();
+            let n = if frame == 3 { 3 } else { 2 };
+            gsd_file.write_chunk("varying", &vec![0u32; n]).unwrap();
+            if frame < 2 {
+                gsd_file.write_chunk("partial", &vec![1u8]).unwrap();
+            }
+            gsd_file.end_frame().unwrap();
+        }
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(
+        gsd_file
+            .read_chunk_series::<f32>("particles/position")
+            .unwrap(),
+        positions
+    );
+    assert!(matches!(
+        gsd_file.read_chunk_series::<u32>("varying"),
+        Err(GsdError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        gsd_file.read_chunk_series::<u8>("partial"),
+        Err(GsdError::ChunkNotFound { frame: 2, .. })
+    ));
+    assert!(matches!(
+        gsd_file.read_chunk_series::<f64>("particles/position"),
+        Err(GsdError::TypeMismatch { .. })
+    ));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_diff_frames() {
+    let rusty_fname = get_test_file_name("fl_diff_frames");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file
+            .write_chunk("configuration/step", &vec![0u64])
+            .unwrap();
+        gsd_file.write_chunk("particles/N", &vec![2u32]).unwrap();
+        gsd_file
+            .write_chunk("particles/position", &vec![0.0f32; 6])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+        // frame 1 rewrites the step, repeats the positions and inherits N
+        gsd_file
+            .write_chunk("configuration/step", &vec![1u64])
+            .unwrap();
+        gsd_file
+            .write_chunk("particles/position", &vec![0.0f32; 6])
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    let diff = |name: &str, present_in_a, present_in_b, bytes_equal| fl::ChunkDiff {
+        name: name.to_owned(),
+        present_in_a,
+        present_in_b,
+        bytes_equal,
+    };
+    let mut diffs = gsd_file.diff_frames(0, 1).unwrap();
+    diffs.sort_by(|a, b| a.name.cmp(&b.name));
+    assert_eq!(
+        diffs,
+        vec![
+            diff("configuration/step", true, true, false),
+            diff("particles/N", true, false, false),
+            diff("particles/position", true, true, true),
+        ]
+    );
+    assert!(gsd_file
+        .diff_frames(1, 1)
+        .unwrap()
+        .iter()
+        .all(|d| d.bytes_equal));
+    assert!(matches!(
+        gsd_file.diff_frames(0, 2),
+        Err(GsdError::FrameOutOfRange { frame: 2, .. })
+    ));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_open_read_and_create() {
+    let rusty_fname = get_test_file_name("fl_open_read_and_create");
+    assert!(fl::GSDFile::open_read(&rusty_fname).is_err());
+
+    let mut gsd_file =
+        fl::GSDFile::create(&rusty_fname, "My application", "hoomd", (1, 4)).unwrap();
+    assert_eq!(gsd_file.mode(), "wb+");
+    gsd_file
+        .write_chunk("configuration/step", &vec![5u64])
+        .unwrap();
+    gsd_file.end_frame().unwrap();
+    // created files can be read back through the same handle
+    assert_eq!(
+        gsd_file
+            .read_chunk_scalar::<u64>(0, "configuration/step")
+            .unwrap(),
+        5
+    );
+    drop(gsd_file);
+
+    let gsd_file = fl::GSDFile::open_read(&rusty_fname).unwrap();
+    assert_eq!(gsd_file.mode(), "rb");
+    assert_eq!(gsd_file.application(), "My application");
+    assert_eq!(gsd_file.schema(), "hoomd");
+    assert_eq!(gsd_file.schema_version(), (1, 4));
+    assert_eq!(gsd_file.nframes(), 1);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_write_chunk_inputs() {
+    let rusty_fname = get_test_file_name("fl_write_chunk_inputs");
+    let data = ndarray::arr2(&[[1.0f32, 2.0], [3.0, 4.0]]);
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file.write_chunk("owned", data.clone()).unwrap();
+        gsd_file.write_chunk("reference", &data).unwrap();
+        gsd_file.write_chunk("view", data.view()).unwrap();
+        gsd_file
+            .write_chunk("cow", ndarray::CowArray::from(data.view()))
+            .unwrap();
+        gsd_file
+            .write_chunk("owned_cow", ndarray::CowArray::from(data.clone()))
+            .unwrap();
+        // transposed arrays are written in standard layout
+        gsd_file.write_chunk("transposed", data.t()).unwrap();
+        gsd_file.write_chunk("vec", &vec![1u8, 2]).unwrap();
+        gsd_file.write_chunk("slice", &[1u8, 2][..]).unwrap();
+        gsd_file.write_chunk("array", &[1u8, 2]).unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    for name in ["owned", "reference", "view", "cow", "owned_cow"] {
+        assert_eq!(gsd_file.read_chunk::<f32>(0, name).unwrap(), data);
+    }
+    assert_eq!(
+        gsd_file.read_chunk::<f32>(0, "transposed").unwrap(),
+        data.t()
+    );
+    for name in ["vec", "slice", "array"] {
+        assert_eq!(
+            gsd_file.read_chunk::<u8>(0, name).unwrap(),
+            ndarray::arr2(&[[1u8], [2]])
+        );
+    }
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_read_chunk_with_dim() {
+    let rusty_fname = get_test_file_name("fl_read_chunk_with_dim");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file
+            .write_chunk(
+                "particles/position",
+                &ndarray::arr2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]),
+            )
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(
+        gsd_file
+            .read_chunk_with_dim::<f32, 3>(0, "particles/position")
+            .unwrap(),
+        vec![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]
+    );
+    match gsd_file.read_chunk_with_dim::<f32, 4>(0, "particles/position") {
+        Err(GsdError::InvalidArgument(message)) => {
+            assert!(message.contains("expected 4 columns"));
+            assert!(message.ends_with("has 3"));
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_steps_and_boxes() {
+    let rusty_fname = get_test_file_name("hoomd_steps_and_boxes");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        // an NPT-style run: the box shrinks in frames 1 and 3, frame 2 inherits from frame 0
+        let boxes = [
+            Some([10.0f32, 10.0, 10.0, 0.0, 0.0, 0.0]),
+            Some([9.0, 9.0, 9.0, 0.0, 0.0, 0.0]),
+            None,
+            Some([8.0, 8.5, 9.0, 0.1, 0.0, 0.0]),
+        ];
+        for (frame, box_) in boxes.iter().enumerate() {
+            if frame != 2 {
+                gsd_file
+                    .write_chunk("configuration/step", &vec![100 * frame as u64])
+                    .unwrap();
+            }
+            if let Some(box_) = box_ {
+                gsd_file.write_chunk("configuration/box", box_).unwrap();
+            }
+            gsd_file.end_frame().unwrap();
+        }
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    assert_eq!(hoomd_file.steps().unwrap(), vec![0, 100, 0, 300]);
+    assert_eq!(
+        hoomd_file.boxes().unwrap(),
+        ndarray::arr2(&[
+            [10.0f32, 10.0, 10.0, 0.0, 0.0, 0.0],
+            [9.0, 9.0, 9.0, 0.0, 0.0, 0.0],
+            [10.0, 10.0, 10.0, 0.0, 0.0, 0.0],
+            [8.0, 8.5, 9.0, 0.1, 0.0, 0.0],
+        ])
+    );
+    for (frame, snap) in hoomd_file.iter().enumerate() {
+        assert_eq!(
+            snap.configuration.step(),
+            hoomd_file.steps().unwrap()[frame]
+        );
+    }
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_write_chunk_if_changed() {
+    let rusty_fname = get_test_file_name("fl_write_chunk_if_changed");
+    let box_ = vec![10.0f32, 10.0, 10.0, 0.0, 0.0, 0.0];
+    let mut gsd_file = fl::open!(&rusty_fname, "wb+", "test", "hoomd", (1, 4)).unwrap();
+    gsd_file.write_chunk("configuration/box", &box_).unwrap();
+    gsd_file.write_chunk("particles/N", &vec![4u32]).unwrap();
+    gsd_file.end_frame().unwrap();
+
+    assert!(!gsd_file
+        .write_chunk_if_changed("configuration/box", &box_, 0)
+        .unwrap());
+    assert!(gsd_file
+        .write_chunk_if_changed("particles/N", &vec![5u32], 0)
+        .unwrap());
+    // chunks missing from the reference frame are written
+    assert!(gsd_file
+        .write_chunk_if_changed("particles/types", &vec![0u8], 0)
+        .unwrap());
+    gsd_file.end_frame().unwrap();
+    // so are chunks of a different type or shape
+    assert!(gsd_file
+        .write_chunk_if_changed("configuration/box", &vec![10.0f64; 6], 0)
+        .unwrap());
+    gsd_file.end_frame().unwrap();
+
+    assert!(!gsd_file.chunk_exists(1, "configuration/box"));
+    assert!(gsd_file.chunk_exists(1, "particles/N"));
+    assert!(gsd_file.chunk_exists(2, "configuration/box"));
+    assert!(matches!(
+        gsd_file.write_chunk_if_changed("particles/N", &vec![4u32], 3),
+        Err(GsdError::FrameOutOfRange { frame: 3, .. })
+    ));
+    drop(gsd_file);
+
+    let mut gsd_file = fl::open!(&rusty_fname, "ab").unwrap();
+    assert!(matches!(
+        gsd_file.write_chunk_if_changed("particles/N", &vec![4u32], 0),
+        Err(GsdError::NotReadable(_))
+    ));
+    drop(gsd_file);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_frames() {
+    let rusty_fname = get_test_file_name("hoomd_frames");
+    write_steps(&rusty_fname, 10);
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+
+    let frames = hoomd_file.frames().range(1..8).step_by(2);
+    assert_eq!(
+        steps(frames.iter()),
+        steps(hoomd_file.view((1..8, 2)).into_iter())
+    );
+    assert_eq!(steps(frames.into_iter()), vec![1, 3, 5, 7]);
+    assert_eq!(
+        steps(hoomd_file.frames().into_iter()),
+        (0..10).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        steps(hoomd_file.frames().step_by(2).step_by(2).iter()),
+        vec![0, 4, 8]
+    );
+    assert_eq!(steps(hoomd_file.frames().range(8..20).iter()), vec![8, 9]);
+    assert_eq!(steps(hoomd_file.frames().range(12..20).iter()), vec![]);
+    let last = hoomd_file.frames().step_by(3).try_iter().last().unwrap();
+    assert_eq!(last.unwrap().configuration.step(), 9);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_read_modify_write() {
+    let rusty_fname = get_test_file_name("fl_read_modify_write");
+    write_steps(&rusty_fname, 2);
+
+    let mut gsd_file = fl::open!(&rusty_fname, "rb+").unwrap();
+    assert_eq!(gsd_file.nframes(), 2);
+    assert_eq!(
+        gsd_file
+            .read_chunk_scalar::<u64>(0, "configuration/step")
+            .unwrap(),
+        0
+    );
+    gsd_file
+        .write_chunk("configuration/step", &vec![2u64])
+        .unwrap();
+    gsd_file.end_frame().unwrap();
+    assert_eq!(gsd_file.nframes(), 3);
+    for frame in 0..3 {
+        assert_eq!(
+            gsd_file
+                .read_chunk_scalar::<u64>(frame, "configuration/step")
+                .unwrap(),
+            frame as u64
+        );
+    }
+    drop(gsd_file);
+
+    // trajectories opened "rb+" append frames that inherit from the existing frame 0
+    let mut hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb+").unwrap());
+    let mut snap = hoomd_file.index(0);
+    snap.configuration = hoomd::ConfigurationData::new(3, 3, snap.configuration.box_());
+    hoomd_file.append(&snap).unwrap();
+    assert_eq!(hoomd_file.len(), 4);
+    assert_eq!(steps(hoomd_file.iter()), vec![0, 1, 2, 3]);
+    drop(hoomd_file);
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(gsd_file.nframes(), 4);
+    assert!(gsd_file.validate().is_ok());
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_frame_hash() {
+    let rusty_fname = get_test_file_name("fl_frame_hash");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        let position = ndarray::arr2(&[[0.0f32, 1.0, 2.0], [3.0, 4.0, 5.0]]);
+        for _ in 0..2 {
+            gsd_file.write_chunk("particles/N", &vec![2u32]).unwrap();
+            gsd_file
+                .write_chunk("particles/position", &position)
+                .unwrap();
+            gsd_file.end_frame().unwrap();
+        }
+        // chunks written in a different order
+        gsd_file
+            .write_chunk("particles/position", &position)
+            .unwrap();
+        gsd_file.write_chunk("particles/N", &vec![2u32]).unwrap();
+        gsd_file.end_frame().unwrap();
+        // one value changed
+        gsd_file.write_chunk("particles/N", &vec![2u32]).unwrap();
+        gsd_file
+            .write_chunk("particles/position", &(&position + 1.0))
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+        // same bytes, different type
+        gsd_file.write_chunk("particles/N", &vec![2i32]).unwrap();
+        gsd_file
+            .write_chunk("particles/position", &position)
+            .unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    let hashes: Vec<u64> = (0..5)
+        .map(|frame| gsd_file.frame_hash(frame).unwrap())
+        .collect();
+    assert_eq!(hashes[0], hashes[1]);
+    assert_eq!(hashes[0], hashes[2]);
+    assert_ne!(hashes[0], hashes[3]);
+    assert_ne!(hashes[0], hashes[4]);
+    assert!(matches!(
+        gsd_file.frame_hash(5),
+        Err(GsdError::FrameOutOfRange { .. })
+    ));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_copy_frame_remapped() {
+    let rusty_fname = get_test_file_name("fl_copy_frame_remapped");
+    let copy_fname = get_test_file_name("fl_copy_frame_remapped_copy");
+    let pos = ndarray::arr2(&[[0.0f64, 1.0, 2.0], [3.0, 4.0, 5.0]]);
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "custom", "custom", (1, 0)).unwrap();
+        gsd_file.write_chunk("pos", &pos).unwrap();
+        gsd_file.write_chunk("particles/N", &vec![2u32]).unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let src = fl::open!(&rusty_fname, "rb").unwrap();
+    let mut dst = fl::open!(&copy_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+    let rename: std::collections::HashMap<String, String> =
+        [("pos".to_owned(), "particles/position".to_owned())].into();
+    fl::copy_frame_remapped(&src, 0, &mut dst, &rename).unwrap();
+    dst.end_frame().unwrap();
+    assert!(matches!(
+        fl::copy_frame_remapped(&src, 1, &mut dst, &rename),
+        Err(GsdError::FrameOutOfRange { frame: 1, .. })
+    ));
+    drop(dst);
+
+    let copy = fl::open!(&copy_fname, "rb").unwrap();
+    let mut names = copy.chunk_names(0);
+    names.sort();
+    assert_eq!(names, vec!["particles/N", "particles/position"]);
+    // the positions keep their double precision type
+    assert_eq!(
+        copy.read_chunk::<f64>(0, "particles/position").unwrap(),
+        pos
+    );
+    assert_eq!(copy.read_chunk_scalar::<u32>(0, "particles/N").unwrap(), 2);
+
+    safely_remove_file_if_exists(&rusty_fname);
+    safely_remove_file_if_exists(&copy_fname);
+}
+
+#[test]
+fn fl_exclusive_create() {
+    let rusty_fname = get_test_file_name("fl_exclusive_create");
+    safely_remove_file_if_exists(&rusty_fname);
+
+    let mut gsd_file = fl::open!(&rusty_fname, "xb", "test", "hoomd", (1, 4)).unwrap();
+    gsd_file.write_chunk("data", &vec![1u8]).unwrap();
+    gsd_file.end_frame().unwrap();
+    drop(gsd_file);
+
+    for mode in ["xb", "xb+"] {
+        match fl::open!(&rusty_fname, mode, "test", "hoomd", (1, 4)) {
+            Err(GsdError::AlreadyExists(name)) => assert_eq!(name, rusty_fname),
+            Err(err) => panic!("unexpected error {}", err),
+            Ok(_) => panic!("{} opened an existing file", mode),
+        }
+    }
+    // the existing file is left untouched
+    assert_eq!(fl::open!(&rusty_fname, "rb").unwrap().nframes(), 1);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_file_access() {
+    let rusty_fname = get_test_file_name("hoomd_file_access");
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file
+            .file_mut()
+            .write_chunk("custom/energy", &vec![-1.5f64])
+            .unwrap();
+        let mut snap = hoomd::OwnedSnapshot::default();
+        snap.configuration = hoomd::ConfigurationData::new(10, 3, [1.0; 6]);
+        hoomd_file.append(&snap).unwrap();
+        assert_eq!(hoomd_file.file().nframes(), 1);
+    }
+
+    let hoomd_file = hoomd::HOOMDTrajectory::new(fl::open!(&rusty_fname, "rb").unwrap());
+    assert_eq!(hoomd_file.index(0).configuration.step(), 10);
+    let gsd_file = hoomd_file.into_inner();
+    assert_eq!(
+        gsd_file
+            .read_chunk_scalar::<f64>(0, "custom/energy")
+            .unwrap(),
+        -1.5
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn hoomd_write_particle_types() {
+    let rusty_fname = get_test_file_name("hoomd_write_particle_types");
+    let types: Vec<String> = ["A", "B", "solvent"]
+        .iter()
+        .map(|&t| t.to_owned())
+        .collect();
+
+    let mut snap = hoomd::OwnedSnapshot::default();
+    snap.particles.n = 3;
+    snap.particles.typeid = Some(vec![0, 1, 2]);
+    snap.particles.types = Some(types.clone());
+    {
+        let mut hoomd_file = hoomd_open!(&rusty_fname, "wb");
+        hoomd_file.append(&snap).unwrap();
+        hoomd_file.append(&snap).unwrap();
+        let mut renamed = snap.clone();
+        renamed.particles.types = Some(vec![]);
+        hoomd_file.append(&renamed).unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    assert_eq!(
+        gsd_file.chunk_info(0, "particles/types").unwrap().type_,
+        GSDType::INT8
+    );
+    // unchanged types are inherited from frame 0
+    assert!(!gsd_file.chunk_exists(1, "particles/types"));
+    let hoomd_file = hoomd::HOOMDTrajectory::new(gsd_file);
+    assert_eq!(hoomd_file.index(0).particles, snap.particles);
+    assert_eq!(hoomd_file.index(1).particles.types, Some(types));
+    assert_eq!(hoomd_file.index(1).particles.type_name(2), Some("solvent"));
+    // empty lists fall back to the default type
+    assert_eq!(
+        hoomd_file.index(2).particles.types,
+        Some(vec!["A".to_owned()])
+    );
+
+    // so do absent ones, frame 0 always stores the table
+    let untyped_fname = get_test_file_name("hoomd_write_particle_types_untyped");
+    {
+        let mut hoomd_file = hoomd_open!(&untyped_fname, "wb");
+        snap.particles.types = None;
+        hoomd_file.append(&snap).unwrap();
+        hoomd_file.append(&snap).unwrap();
+    }
+    let gsd_file = fl::open!(&untyped_fname, "rb").unwrap();
+    assert!(gsd_file.chunk_exists(0, "particles/types"));
+    assert!(!gsd_file.chunk_exists(1, "particles/types"));
+    let hoomd_file = hoomd::HOOMDTrajectory::new(gsd_file);
+    for frame in 0..2 {
+        assert_eq!(
+            hoomd_file.index(frame).particles.types,
+            Some(vec!["A".to_owned()])
+        );
+    }
+
+    safely_remove_file_if_exists(&rusty_fname);
+    safely_remove_file_if_exists(&untyped_fname);
+}
+
+#[test]
+fn fl_chunk_lookup() {
+    let rusty_fname = get_test_file_name("fl_chunk_lookup");
+    {
+        let mut gsd_file = fl::open!(&rusty_fname, "wb", "test", "hoomd", (1, 4)).unwrap();
+        gsd_file
+            .write_chunk("particles/position", &ndarray::Array2::<f32>::zeros((4, 3)))
+            .unwrap();
+        gsd_file.write_chunk("particles/N", &vec![4u32]).unwrap();
+        gsd_file.end_frame().unwrap();
+        gsd_file.write_chunk("particles/N", &vec![4u32]).unwrap();
+        gsd_file.end_frame().unwrap();
+    }
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    for frame in 0..3 {
+        for name in ["particles/position", "particles/N", "missing"] {
+            let two_calls = if gsd_file.chunk_exists(frame, name) {
+                Some(gsd_file.chunk_info(frame, name).unwrap())
+            } else {
+                None
+            };
+            assert_eq!(gsd_file.chunk_lookup(frame, name).unwrap(), two_calls);
+        }
+    }
+    assert_eq!(
+        gsd_file.chunk_lookup(0, "particles/position").unwrap(),
+        Some(fl::ChunkInfo {
+            type_: GSDType::FLOAT,
+            n: 4,
+            m: 3,
+            flags: 0
+        })
+    );
+    assert_eq!(
+        gsd_file.chunk_lookup(1, "particles/position").unwrap(),
+        None
+    );
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_try_clone_read() {
+    fn assert_send<T: Send>() {}
+    assert_send::<fl::GSDFile>();
+    assert_send::<hoomd::HOOMDTrajectory>();
+
+    let rusty_fname = get_test_file_name("fl_try_clone_read");
+    write_steps(&rusty_fname, 8);
+
+    let gsd_file = fl::open!(&rusty_fname, "rb+").unwrap();
+    let clone = gsd_file.try_clone_read().unwrap();
+    assert_eq!(clone.mode(), "rb");
+    let steps = std::thread::spawn(move || {
+        (0..clone.nframes())
+            .map(|frame| {
+                clone
+                    .read_chunk_scalar::<u64>(frame, "configuration/step")
+                    .unwrap()
+            })
+            .collect::<Vec<_>>()
+    })
+    .join()
+    .unwrap();
+    assert_eq!(steps, (0..8).collect::<Vec<u64>>());
+    // the original handle is unaffected
+    assert_eq!(gsd_file.nframes(), 8);
+
+    safely_remove_file_if_exists(&rusty_fname);
+}
+
+#[test]
+fn fl_chunk_info_flags() {
+    let rusty_fname = get_test_file_name("fl_chunk_info_flags");
+    write_steps(&rusty_fname, 2);
+
+    let gsd_file = fl::open!(&rusty_fname, "rb").unwrap();
+    // GSD writes uncompressed chunks with no flags set
+    let info = gsd_file.chunk_info(1, "configuration/step").unwrap();
+    assert_eq!(info.flags, 0);
+    assert_eq!(
+        gsd_file.chunk_lookup(1, "configuration/step").unwrap(),
+        Some(info)
+    );
+    assert!(gsd_file.chunks().all(|(_, _, info)| info.flags == 0));
+
+    safely_remove_file_if_exists(&rusty_fname);
+}